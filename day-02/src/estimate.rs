@@ -0,0 +1,448 @@
+use crate::{Bag, Game};
+
+/// Half of the 95% quantile of the chi-squared distribution with one degree
+/// of freedom, used to cut the profile log-likelihood.
+const PROFILE_CUTOFF: f64 = 1.920_729_4;
+
+/// Tail mass excluded on either side of a 95% credible interval.
+const CREDIBLE_TAIL: f64 = 0.025;
+
+/// Prior belief about the contents of the bag.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Prior {
+    /// Every bag inside the search limit is equally likely.
+    Uniform,
+    /// Each colour count is independently Poisson distributed around a mean.
+    Poisson { red: f64, green: f64, blue: f64 },
+}
+
+/// 95% bounds on a colour count. The upper bound is `None` when the
+/// interval runs into the search limit, so the draws put no bound on it.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Interval {
+    pub low: u32,
+    pub high: Option<u32>,
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.high {
+            Some(high) => write!(f, "[{}, {}]", self.low, high),
+            None => write!(f, "[{}, unbounded)", self.low),
+        }
+    }
+}
+
+/// The estimate for one colour. The count is `None` when the best bag has as
+/// many cubes of any colour as the search allows: the likelihood is then
+/// still rising at the limit, and every count of the best bag moves with the
+/// limit rather than saying anything about the bag.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Colour {
+    pub count: Option<u32>,
+    pub interval: Interval,
+}
+
+impl std::fmt::Display for Colour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.count {
+            Some(count) => write!(f, "{} {}", count, self.interval),
+            None => write!(f, "unbounded {}", self.interval),
+        }
+    }
+}
+
+/// A point estimate of the bag contents together with 95% bounds on each
+/// colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub red: Colour,
+    pub green: Colour,
+    pub blue: Colour,
+}
+
+impl Estimate {
+    fn new(bag: &Bag, limit: &Bag, red: Interval, green: Interval, blue: Interval) -> Self {
+        let capped = bag.red >= limit.red || bag.green >= limit.green || bag.blue >= limit.blue;
+        let colour = |count: u32, interval: Interval| Colour {
+            count: (!capped).then_some(count),
+            interval,
+        };
+        Self {
+            red: colour(bag.red, red),
+            green: colour(bag.green, green),
+            blue: colour(bag.blue, blue),
+        }
+    }
+}
+
+/// Log-likelihood of a game's draws, split into the per colour terms and the
+/// term depending only on the total number of cubes in the bag.
+///
+/// Each draw is a multivariate hypergeometric sample taken without
+/// replacement, and the cubes are put back before the next draw, so
+///
+///   ln L(R, G, B) = red[R] + green[G] + blue[B] - total[R + G + B]
+///
+/// Impossible bags (fewer cubes than were drawn) are `NEG_INFINITY`.
+struct Likelihood {
+    red: Vec<f64>,
+    green: Vec<f64>,
+    blue: Vec<f64>,
+    total: Vec<f64>,
+}
+
+impl Likelihood {
+    fn new(game: &Game, limit: &Bag) -> Self {
+        let ln_fact = ln_factorials((limit.red + limit.green + limit.blue) as usize);
+        let ln_choose = |n: u32, k: u32| {
+            if k > n {
+                f64::NEG_INFINITY
+            } else {
+                ln_fact[n as usize] - ln_fact[k as usize] - ln_fact[(n - k) as usize]
+            }
+        };
+
+        let colour = |limit: u32, drawn: fn(&Bag) -> u32| {
+            (0..=limit)
+                .map(|n| game.bags.iter().map(|b| ln_choose(n, drawn(b))).sum())
+                .collect::<Vec<f64>>()
+        };
+
+        Self {
+            red: colour(limit.red, |b| b.red),
+            green: colour(limit.green, |b| b.green),
+            blue: colour(limit.blue, |b| b.blue),
+            total: colour(limit.red + limit.green + limit.blue, |b| {
+                b.red + b.green + b.blue
+            }),
+        }
+    }
+
+    fn at(&self, red: u32, green: u32, blue: u32) -> f64 {
+        self.red[red as usize] + self.green[green as usize] + self.blue[blue as usize]
+            - self.total[(red + green + blue) as usize]
+    }
+}
+
+impl Game {
+    /// `limit` raised, colour by colour, to the largest draw, so the search
+    /// always holds at least one bag that could have produced the game.
+    fn search_limit(&self, limit: &Bag) -> Bag {
+        let min = self.max();
+        Bag {
+            red: limit.red.max(min.red),
+            green: limit.green.max(min.green),
+            blue: limit.blue.max(min.blue),
+        }
+    }
+
+    /// Maximum-likelihood estimate of the bag contents, searching every bag up
+    /// to `limit`, or up to the largest draw where that is bigger.
+    ///
+    /// The bounds are 95% profile-likelihood intervals. Ties are broken
+    /// towards the smallest bag. Drawing without replacement from a bigger
+    /// bag gets ever closer to drawing with replacement, so the likelihood
+    /// often keeps rising all the way to the limit. If the best bag reaches it
+    /// in any colour, no count is reported, and bounds that reach it are
+    /// reported as unbounded.
+    pub fn mle(&self, limit: &Bag) -> Estimate {
+        let min = self.max();
+        let limit = &self.search_limit(limit);
+        let likelihood = Likelihood::new(self, limit);
+
+        let mut best = (f64::NEG_INFINITY, min.clone());
+        let mut red = vec![f64::NEG_INFINITY; limit.red as usize + 1];
+        let mut green = vec![f64::NEG_INFINITY; limit.green as usize + 1];
+        let mut blue = vec![f64::NEG_INFINITY; limit.blue as usize + 1];
+
+        for r in min.red..=limit.red {
+            for g in min.green..=limit.green {
+                for b in min.blue..=limit.blue {
+                    let ll = likelihood.at(r, g, b);
+                    if ll > best.0 + f64::EPSILON {
+                        best = (
+                            ll,
+                            Bag {
+                                red: r,
+                                green: g,
+                                blue: b,
+                            },
+                        );
+                    }
+                    red[r as usize] = red[r as usize].max(ll);
+                    green[g as usize] = green[g as usize].max(ll);
+                    blue[b as usize] = blue[b as usize].max(ll);
+                }
+            }
+        }
+
+        let cutoff = best.0 - PROFILE_CUTOFF;
+        Estimate::new(
+            &best.1,
+            limit,
+            profile_interval(&red, cutoff),
+            profile_interval(&green, cutoff),
+            profile_interval(&blue, cutoff),
+        )
+    }
+
+    /// Bayesian estimate of the bag contents, searching every bag up to
+    /// `limit`, or up to the largest draw where that is bigger.
+    ///
+    /// The point estimate is the posterior mode and the bounds are 95%
+    /// equal-tailed credible intervals of each colour's marginal posterior.
+    /// As with `mle`, a mode on the limit leaves every count unreported, and
+    /// bounds that reach it are reported as unbounded.
+    pub fn posterior(&self, limit: &Bag, prior: &Prior) -> Estimate {
+        let min = self.max();
+        let limit = &self.search_limit(limit);
+        let likelihood = Likelihood::new(self, limit);
+
+        let ln_prior = |r: u32, g: u32, b: u32| match prior {
+            Prior::Uniform => 0.0,
+            Prior::Poisson { red, green, blue } => {
+                ln_poisson(r, *red) + ln_poisson(g, *green) + ln_poisson(b, *blue)
+            }
+        };
+
+        let mut log_posterior = Vec::new();
+        let mut best = (f64::NEG_INFINITY, min.clone());
+        for r in min.red..=limit.red {
+            for g in min.green..=limit.green {
+                for b in min.blue..=limit.blue {
+                    let lp = likelihood.at(r, g, b) + ln_prior(r, g, b);
+                    if lp > best.0 + f64::EPSILON {
+                        best = (
+                            lp,
+                            Bag {
+                                red: r,
+                                green: g,
+                                blue: b,
+                            },
+                        );
+                    }
+                    log_posterior.push((r, g, b, lp));
+                }
+            }
+        }
+
+        let mut red = vec![0.0; limit.red as usize + 1];
+        let mut green = vec![0.0; limit.green as usize + 1];
+        let mut blue = vec![0.0; limit.blue as usize + 1];
+        for (r, g, b, lp) in log_posterior {
+            let weight = (lp - best.0).exp();
+            red[r as usize] += weight;
+            green[g as usize] += weight;
+            blue[b as usize] += weight;
+        }
+
+        Estimate::new(
+            &best.1,
+            limit,
+            credible_interval(&red),
+            credible_interval(&green),
+            credible_interval(&blue),
+        )
+    }
+}
+
+fn ln_factorials(n: usize) -> Vec<f64> {
+    let mut result = Vec::with_capacity(n + 1);
+    result.push(0.0);
+    for k in 1..=n {
+        result.push(result[k - 1] + (k as f64).ln());
+    }
+    result
+}
+
+fn ln_poisson(k: u32, mean: f64) -> f64 {
+    let ln_fact: f64 = (1..=k).map(|i| (i as f64).ln()).sum();
+    k as f64 * mean.ln() - mean - ln_fact
+}
+
+fn profile_interval(profile: &[f64], cutoff: f64) -> Interval {
+    let mut inside = profile
+        .iter()
+        .enumerate()
+        .filter(|(_, ll)| **ll >= cutoff)
+        .map(|(n, _)| n as u32);
+
+    let low = inside.next().unwrap_or_default();
+    let high = inside.next_back().unwrap_or(low);
+    Interval {
+        low,
+        high: (high as usize + 1 < profile.len()).then_some(high),
+    }
+}
+
+fn credible_interval(marginal: &[f64]) -> Interval {
+    let total: f64 = marginal.iter().sum();
+    let quantile = |p: f64| {
+        let mut cumulative = 0.0;
+        marginal
+            .iter()
+            .position(|w| {
+                cumulative += w / total;
+                cumulative >= p
+            })
+            .unwrap_or(marginal.len() - 1) as u32
+    };
+
+    let high = quantile(1.0 - CREDIBLE_TAIL);
+    Interval {
+        low: quantile(CREDIBLE_TAIL),
+        high: (high as usize + 1 < marginal.len()).then_some(high),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    const LIMIT: Bag = Bag {
+        red: 40,
+        green: 40,
+        blue: 40,
+    };
+
+    fn contains(interval: &Interval, value: u32) -> bool {
+        interval.low <= value && interval.high.is_none_or(|high| value <= high)
+    }
+
+    fn colours(estimate: &Estimate) -> [Colour; 3] {
+        [estimate.red, estimate.green, estimate.blue]
+    }
+
+    #[test]
+    fn test_mle_within_bounds() {
+        let input = include_str!("../input_test_part1.txt");
+
+        for game in parse_input(input) {
+            let estimate = game.mle(&LIMIT);
+            let min = game.max();
+
+            for (colour, min) in colours(&estimate)
+                .iter()
+                .zip([min.red, min.green, min.blue])
+            {
+                assert!(colour.interval.low >= min);
+                if let Some(count) = colour.count {
+                    assert!(count >= min);
+                    assert!(contains(&colour.interval, count));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mle_single_draw() {
+        let game = Game {
+            id: 1,
+            bags: vec![Bag {
+                red: 3,
+                green: 0,
+                blue: 0,
+            }],
+        };
+
+        let estimate = game.mle(&LIMIT);
+        assert_eq!(estimate.red.count, Some(3));
+        assert_eq!(estimate.green.count, Some(0));
+        assert_eq!(estimate.blue.count, Some(0));
+        assert_eq!(estimate.red.interval, Interval { low: 3, high: None });
+        assert_eq!(estimate.red.to_string(), "3 [3, unbounded)");
+    }
+
+    #[test]
+    fn test_mle_rising_to_limit() {
+        // Game 1 of the real input: these draws look more like drawing with
+        // replacement than from any small bag, so the likelihood rises all
+        // the way to the limit on blue and drags the other counts with it.
+        let game = &parse_input(
+            "Game 1: 4 red, 5 blue, 4 green; 7 red, 8 blue, 2 green; 9 blue, 6 red; \
+             1 green, 3 red, 7 blue; 3 green, 7 red",
+        )[0];
+
+        let estimate = game.mle(&LIMIT);
+        for colour in colours(&estimate) {
+            assert_eq!(colour.count, None);
+        }
+        assert_eq!(estimate.blue.interval.high, None);
+        assert!(estimate.green.interval.high.is_some());
+        assert_eq!(estimate.blue.to_string(), "unbounded [13, unbounded)");
+    }
+
+    #[test]
+    fn test_draw_above_limit() {
+        let game = Game {
+            id: 1,
+            bags: vec![Bag {
+                red: 50,
+                green: 2,
+                blue: 0,
+            }],
+        };
+
+        let estimate = game.mle(&LIMIT);
+        assert_eq!(estimate.red.count, None);
+        assert_eq!(
+            estimate.red.interval,
+            Interval {
+                low: 50,
+                high: None
+            }
+        );
+        assert!(estimate.green.interval.low >= 2);
+
+        let estimate = game.posterior(&LIMIT, &Prior::Uniform);
+        assert_eq!(
+            estimate.red.interval,
+            Interval {
+                low: 50,
+                high: None
+            }
+        );
+        assert!(contains(&estimate.green.interval, 2));
+    }
+
+    #[test]
+    fn test_posterior_uniform_mode_is_mle() {
+        let input = include_str!("../input_test_part1.txt");
+
+        for game in parse_input(input) {
+            let posterior = game.posterior(&LIMIT, &Prior::Uniform);
+            let mle = game.mle(&LIMIT);
+            for (posterior, mle) in colours(&posterior).iter().zip(colours(&mle)) {
+                assert_eq!(posterior.count, mle.count);
+            }
+        }
+    }
+
+    #[test]
+    fn test_posterior_poisson_prior() {
+        let game = Game {
+            id: 1,
+            bags: vec![Bag {
+                red: 2,
+                green: 0,
+                blue: 0,
+            }],
+        };
+
+        let estimate = game.posterior(
+            &LIMIT,
+            &Prior::Poisson {
+                red: 12.0,
+                green: 13.0,
+                blue: 14.0,
+            },
+        );
+        assert!(contains(&estimate.red.interval, 12));
+        assert!(contains(&estimate.green.interval, 13));
+        assert!(contains(&estimate.blue.interval, 14));
+        assert!(estimate.red.interval.high.is_some());
+        assert!(estimate.red.count.is_some());
+    }
+}
@@ -1,5 +1,9 @@
 use std::cmp::max;
 
+use estimate::Prior;
+
+mod estimate;
+
 #[derive(Debug, Clone)]
 struct Game {
     id: usize,
@@ -106,9 +110,62 @@ fn part_2(input: &str) -> u32 {
 
     games.iter().map(|g| g.max().power()).sum()
 }
+
+fn estimate(input: &str, limit: u32) {
+    let limit = Bag {
+        red: limit,
+        green: limit,
+        blue: limit,
+    };
+    let prior = if std::env::args().any(|arg| arg == "--uniform") {
+        Prior::Uniform
+    } else {
+        Prior::Poisson {
+            red: 12.0,
+            green: 13.0,
+            blue: 14.0,
+        }
+    };
+
+    for game in parse_input(input) {
+        for (name, estimate) in [
+            ("mle", game.mle(&limit)),
+            ("posterior", game.posterior(&limit, &prior)),
+        ] {
+            println!(
+                "Game {} {}: red {}, green {}, blue {}",
+                game.id, name, estimate.red, estimate.green, estimate.blue,
+            );
+        }
+    }
+}
+
 fn main() {
     let input = include_str!("../input.txt");
 
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|arg| arg == "--estimate") {
+        // `--limit N` caps every colour count the search considers.
+        let limit = match args.iter().position(|arg| arg == "--limit") {
+            None => Ok(60),
+            Some(i) => args
+                .get(i + 1)
+                .ok_or_else(|| "usage: --limit <cubes per colour>".to_string())
+                .and_then(|n| {
+                    n.parse::<u32>()
+                        .map_err(|e| format!("invalid limit {n:?}: {e}"))
+                }),
+        };
+        match limit {
+            Ok(limit) => estimate(input, limit),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     println!("Part 1: {}", part_1(input));
     println!("Part 2: {}", part_2(input));
 }
@@ -0,0 +1,182 @@
+use std::{collections::BTreeSet, ops::RangeInclusive, str::FromStr};
+
+use crate::schematic::Schematic;
+
+/// How the numbers around a matching symbol are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregation {
+    /// The combined value, or `None` if it overflows a `u64`.
+    fn apply(&self, mut values: impl Iterator<Item = u64>) -> Option<u64> {
+        match self {
+            Aggregation::Product => values.try_fold(1, u64::checked_mul),
+            Aggregation::Sum => values.try_fold(0, u64::checked_add),
+            Aggregation::Min => Some(values.min().unwrap_or_default()),
+            Aggregation::Max => Some(values.max().unwrap_or_default()),
+        }
+    }
+}
+
+/// A rule deciding which symbols count as gears and what each one is worth.
+///
+/// A symbol matches when it is in `symbols` (or `symbols` is `None`, meaning
+/// any symbol) and the number of distinct numbers touching it is within
+/// `arity`. The rule's total is the sum, over every matching symbol, of its
+/// neighbouring numbers combined with `aggregation`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearRule {
+    pub symbols: Option<BTreeSet<char>>,
+    pub arity: RangeInclusive<usize>,
+    pub aggregation: Aggregation,
+}
+
+impl Default for GearRule {
+    /// The puzzle's rule: a `*` touching exactly two numbers, multiplied.
+    fn default() -> Self {
+        Self {
+            symbols: Some(BTreeSet::from(['*'])),
+            arity: 2..=2,
+            aggregation: Aggregation::Product,
+        }
+    }
+}
+
+impl GearRule {
+    /// The rule's total, or an error naming the first symbol whose value, or
+    /// whose addition to the total, overflows a `u64`.
+    pub fn evaluate(&self, schematic: &Schematic) -> Result<u64, String> {
+        schematic
+            .symbols()
+            .filter(|(_, symbol)| self.symbols.as_ref().is_none_or(|s| s.contains(symbol)))
+            .map(|(address, _)| (address, schematic.numbers_touching(address)))
+            .filter(|(_, numbers)| self.arity.contains(&numbers.len()))
+            .try_fold(0_u64, |total, (address, numbers)| {
+                self.aggregation
+                    .apply(
+                        numbers
+                            .into_iter()
+                            .map(|id| schematic.number(id).value as u64),
+                    )
+                    .and_then(|value| total.checked_add(value))
+                    .ok_or_else(|| {
+                        format!(
+                            "the value of the symbol at row {}, column {} overflows a u64",
+                            address.row + 1,
+                            address.col + 1
+                        )
+                    })
+            })
+    }
+}
+
+impl FromStr for GearRule {
+    type Err = String;
+
+    /// Parses `<symbols>:<arity>:<aggregation>`, for example `*:2:product` or
+    /// `#:3..:sum`. The symbols `any` match every symbol, and the arity is
+    /// either a count or a range `a..b`, `a..` or `..b` (both ends inclusive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let (Some(symbols), Some(arity), Some(aggregation), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!(
+                "expected <symbols>:<arity>:<aggregation>, got {s:?}"
+            ));
+        };
+
+        let symbols = match symbols {
+            "any" => None,
+            "" => return Err("no symbols given".to_string()),
+            symbols => Some(symbols.chars().collect()),
+        };
+
+        let count = |n: &str, default: usize| {
+            if n.is_empty() {
+                Ok(default)
+            } else {
+                n.parse::<usize>()
+                    .map_err(|e| format!("invalid arity {n:?}: {e}"))
+            }
+        };
+        let arity = match arity.split_once("..") {
+            Some((low, high)) => count(low, 0)?..=count(high, usize::MAX)?,
+            None => {
+                let n = count(arity, 0)?;
+                n..=n
+            }
+        };
+
+        let aggregation = match aggregation {
+            "product" => Aggregation::Product,
+            "sum" => Aggregation::Sum,
+            "min" => Aggregation::Min,
+            "max" => Aggregation::Max,
+            other => return Err(format!("unknown aggregation {other:?}")),
+        };
+
+        Ok(Self {
+            symbols,
+            arity,
+            aggregation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("*:2:product".parse::<GearRule>(), Ok(GearRule::default()));
+        assert_eq!(
+            "#$:3..:sum".parse::<GearRule>(),
+            Ok(GearRule {
+                symbols: Some(BTreeSet::from(['#', '$'])),
+                arity: 3..=usize::MAX,
+                aggregation: Aggregation::Sum,
+            })
+        );
+        assert!("*:2".parse::<GearRule>().is_err());
+        assert!("*:two:sum".parse::<GearRule>().is_err());
+    }
+
+    #[test]
+    fn test_variants() {
+        let schematic = Schematic::new(include_str!("../test-input.txt"));
+
+        assert_eq!(GearRule::default().evaluate(&schematic), Ok(467835));
+        assert_eq!(
+            "any:1..:sum"
+                .parse::<GearRule>()
+                .unwrap()
+                .evaluate(&schematic),
+            Ok(4361)
+        );
+        assert_eq!(
+            "*:1:max".parse::<GearRule>().unwrap().evaluate(&schematic),
+            Ok(617)
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Six numbers touch the `*`, and 99999^6 does not fit in a u64.
+        let schematic = Schematic::new("99999.99999\n99999*99999\n99999.99999\n");
+        let rule = "any:1..:product".parse::<GearRule>().unwrap();
+        assert_eq!(
+            rule.evaluate(&schematic),
+            Err("the value of the symbol at row 2, column 6 overflows a u64".to_string())
+        );
+
+        let rule = "any:1..:sum".parse::<GearRule>().unwrap();
+        assert_eq!(rule.evaluate(&schematic), Ok(6 * 99999));
+    }
+}
@@ -1,151 +1,28 @@
-use std::collections::{BTreeMap, BTreeSet};
+use gear::GearRule;
+use schematic::Schematic;
 
-#[derive(Debug, Clone)]
-enum Value {
-    Digit(u32),
-    Symbol(char),
-}
+mod gear;
+mod schematic;
 
-#[derive(Debug, Clone, Default, PartialOrd, Ord, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialOrd, Ord, Eq, PartialEq, Hash)]
 struct Address {
     row: i32,
     col: i32,
 }
 
 fn part_1(input: &str) -> u32 {
-    let numbers = input
-        .lines()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            extract_numbers(line)
-                .into_iter()
-                .map(move |(col, len, number)| {
-                    (
-                        (col..col + len)
-                            .map(|c| Address {
-                                row: row as i32,
-                                col: c as i32,
-                            })
-                            .collect::<Vec<_>>(),
-                        number,
-                    )
-                })
-        })
-        .collect::<Vec<(Vec<Address>, u32)>>();
-
-    let grid = input
-        .lines()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.chars().enumerate().map(move |(col, character)| {
-                (
-                    Address {
-                        row: row as i32,
-                        col: col as i32,
-                    },
-                    match character {
-                        '.' => None,
-                        c if c.is_ascii_digit() => Some(Value::Digit(c.to_digit(10).unwrap())),
-                        c => Some(Value::Symbol(c)),
-                    },
-                )
-            })
-        })
-        .collect::<BTreeMap<Address, Option<Value>>>();
-
-    let symbols: BTreeMap<Address, char> = grid
-        .iter()
-        .filter_map(|(address, value)| match value {
-            Some(Value::Symbol(c)) => Some((address.clone(), *c)),
-            _ => None,
-        })
-        .collect();
-
-    let mut sum = 0;
-    for (addresses, number) in numbers {
-        if addresses
-            .iter()
-            .flat_map(bounding_box)
-            .any(|a| symbols.contains_key(&a))
-        {
-            sum += number;
-        }
-    }
+    let schematic = Schematic::new(input);
 
-    sum
+    schematic
+        .part_numbers()
+        .map(|id| schematic.number(id).value)
+        .sum()
 }
 
-fn part_2(input: &str) -> u32 {
-    let numbers = input
-        .lines()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            extract_numbers(line)
-                .into_iter()
-                .map(move |(col, len, number)| {
-                    (
-                        (col..col + len)
-                            .map(|c| Address {
-                                row: row as i32,
-                                col: c as i32,
-                            })
-                            .collect::<Vec<_>>(),
-                        number,
-                    )
-                })
-        })
-        .collect::<Vec<(Vec<Address>, u32)>>();
-
-    let grid = input
-        .lines()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.chars().enumerate().map(move |(col, character)| {
-                (
-                    Address {
-                        row: row as i32,
-                        col: col as i32,
-                    },
-                    match character {
-                        '.' => None,
-                        c if c.is_ascii_digit() => Some(Value::Digit(c.to_digit(10).unwrap())),
-                        c => Some(Value::Symbol(c)),
-                    },
-                )
-            })
-        })
-        .collect::<BTreeMap<Address, Option<Value>>>();
-
-    let gears: BTreeSet<Address> = grid
-        .iter()
-        .filter_map(|(address, value)| match value {
-            Some(Value::Symbol('*')) => Some(address.clone()),
-            _ => None,
-        })
-        .collect();
-
-    dbg!(&gears);
-
-    let mut sum = 0;
-    for gear in gears {
-        let bounding_numbers = numbers
-            .iter()
-            .filter_map(|(addresses, number)| {
-                if addresses.iter().flat_map(bounding_box).any(|a| a == gear) {
-                    Some(number)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
-
-        if bounding_numbers.len() == 2 {
-            println!("Including numbers {:?}", bounding_numbers);
-            sum += bounding_numbers.into_iter().product::<u32>()
-        }
-    }
-
-    sum
+fn part_2(input: &str) -> u64 {
+    GearRule::default()
+        .evaluate(&Schematic::new(input))
+        .expect("the product of two u32 numbers fits in a u64")
 }
 
 fn extract_numbers(input: &str) -> Vec<(usize, usize, u32)> {
@@ -178,45 +55,24 @@ fn extract_numbers(input: &str) -> Vec<(usize, usize, u32)> {
     result
 }
 
-fn bounding_box(address: &Address) -> Vec<Address> {
-    vec![
-        Address {
-            row: address.row - 1,
-            col: address.col - 1,
-        },
-        Address {
-            row: address.row - 1,
-            col: address.col,
-        },
-        Address {
-            row: address.row - 1,
-            col: address.col + 1,
-        },
-        Address {
-            row: address.row,
-            col: address.col - 1,
-        },
-        Address {
-            row: address.row,
-            col: address.col + 1,
-        },
-        Address {
-            row: address.row + 1,
-            col: address.col - 1,
-        },
-        Address {
-            row: address.row + 1,
-            col: address.col,
-        },
-        Address {
-            row: address.row + 1,
-            col: address.col + 1,
-        },
-    ]
-}
-
 fn main() {
     let input = include_str!("../input.txt");
+
+    let mut args = std::env::args().skip_while(|arg| arg != "--rule");
+    if let Some(rule) = args.nth(1) {
+        match rule
+            .parse::<GearRule>()
+            .and_then(|rule| rule.evaluate(&Schematic::new(input)))
+        {
+            Ok(total) => println!("{total}"),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     println!("Part 1: {}", part_1(input));
     println!("Part 2: {}", part_2(input));
 }
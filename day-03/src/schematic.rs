@@ -0,0 +1,158 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{extract_numbers, Address};
+
+/// Index of a number inside a `Schematic`.
+pub type NumberId = usize;
+
+/// A number in the schematic, spanning `len` cells to the right of `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number {
+    pub start: Address,
+    pub len: usize,
+    pub value: u32,
+}
+
+impl Number {
+    /// Every cell bordering the number, including the diagonals.
+    fn perimeter(&self) -> impl Iterator<Item = Address> + '_ {
+        let Address { row, col } = self.start;
+        let end = col + self.len as i32;
+
+        (col - 1..=end)
+            .flat_map(move |c| {
+                [
+                    Address {
+                        row: row - 1,
+                        col: c,
+                    },
+                    Address {
+                        row: row + 1,
+                        col: c,
+                    },
+                ]
+            })
+            .chain([Address { row, col: col - 1 }, Address { row, col: end }])
+    }
+}
+
+/// An engine schematic parsed once and indexed for adjacency queries.
+///
+/// Numbers are stored as spans, with every digit cell pointing back at the
+/// number covering it, and symbols are keyed by their position, so each query
+/// only inspects the handful of cells around its subject.
+#[derive(Debug, Clone, Default)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    digits: HashMap<Address, NumberId>,
+    symbols: HashMap<Address, char>,
+}
+
+impl Schematic {
+    pub fn new(input: &str) -> Self {
+        let mut schematic = Self::default();
+
+        for (row, line) in input.lines().enumerate() {
+            for (col, len, value) in extract_numbers(line) {
+                let id = schematic.numbers.len();
+                schematic.numbers.push(Number {
+                    start: Address {
+                        row: row as i32,
+                        col: col as i32,
+                    },
+                    len,
+                    value,
+                });
+                schematic.digits.extend((col..col + len).map(|c| {
+                    (
+                        Address {
+                            row: row as i32,
+                            col: c as i32,
+                        },
+                        id,
+                    )
+                }));
+            }
+
+            schematic.symbols.extend(
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c != '.' && !c.is_ascii_digit())
+                    .map(|(col, c)| {
+                        (
+                            Address {
+                                row: row as i32,
+                                col: col as i32,
+                            },
+                            c,
+                        )
+                    }),
+            );
+        }
+
+        schematic
+    }
+
+    pub fn number(&self, id: NumberId) -> &Number {
+        &self.numbers[id]
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = (&Address, char)> + '_ {
+        self.symbols.iter().map(|(address, c)| (address, *c))
+    }
+
+    /// The numbers with a digit in one of the eight cells around `address`.
+    pub fn numbers_touching(&self, address: &Address) -> BTreeSet<NumberId> {
+        (-1..=1)
+            .flat_map(|dr| (-1..=1).map(move |dc| (dr, dc)))
+            .filter(|&offset| offset != (0, 0))
+            .filter_map(|(dr, dc)| {
+                self.digits.get(&Address {
+                    row: address.row + dr,
+                    col: address.col + dc,
+                })
+            })
+            .copied()
+            .collect()
+    }
+
+    /// The symbols bordering the number `id`.
+    pub fn symbols_touching(&self, id: NumberId) -> impl Iterator<Item = (Address, char)> + '_ {
+        self.numbers[id]
+            .perimeter()
+            .filter_map(|address| self.symbols.get(&address).map(|c| (address, *c)))
+    }
+
+    /// Every number bordering at least one symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = NumberId> + '_ {
+        (0..self.numbers.len()).filter(|&id| self.symbols_touching(id).next().is_some())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_numbers_touching() {
+        let schematic = Schematic::new(include_str!("../test-input.txt"));
+
+        let values = schematic
+            .numbers_touching(&Address { row: 1, col: 3 })
+            .into_iter()
+            .map(|id| schematic.number(id).value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![467, 35]);
+    }
+
+    #[test]
+    fn test_symbols_touching() {
+        let schematic = Schematic::new(include_str!("../test-input.txt"));
+
+        let symbols = (0..schematic.numbers.len())
+            .map(|id| schematic.symbols_touching(id).map(|(_, c)| c).collect())
+            .collect::<Vec<Vec<char>>>();
+        assert_eq!(symbols[0], vec!['*']);
+        assert!(symbols[1].is_empty());
+    }
+}
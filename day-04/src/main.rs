@@ -1,4 +1,5 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 struct Card {
@@ -8,25 +9,90 @@ struct Card {
 }
 
 impl Card {
+    /// The winning numbers that also appear among the game numbers.
+    fn matches(&self) -> Vec<u32> {
+        let game_numbers: HashSet<u32> = self.game_numbers.iter().copied().collect();
+        self.winning_numbers
+            .iter()
+            .copied()
+            .filter(|n| game_numbers.contains(n))
+            .collect()
+    }
+
     fn winner(&self) -> usize {
-        let mut count = 0;
-        for guess in &self.winning_numbers {
-            if self.game_numbers.contains(guess) {
-                count += 1;
-            }
-        }
-        count
+        self.matches().len()
     }
 
     fn value(&self) -> usize {
-        let num_wins = self.winner();
-        if num_wins == 0 {
-            return 0;
+        Scoring::Doubling.score(self.winner())
+    }
+}
+
+/// How a card's number of matches is turned into points.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+enum Scoring {
+    /// One point for the first match, doubled for every match after it.
+    #[default]
+    Doubling,
+    /// One point per match.
+    Linear,
+    /// The n-th Fibonacci number for n matches (1, 1, 2, 3, 5, ...).
+    Fibonacci,
+    /// The score for n matches is the n-th entry, or the last entry once the
+    /// table runs out.
+    Table(Vec<usize>),
+}
+
+impl Scoring {
+    fn score(&self, matches: usize) -> usize {
+        match self {
+            Scoring::Table(table) => table
+                .get(matches)
+                .or(table.last())
+                .copied()
+                .unwrap_or_default(),
+            _ if matches == 0 => 0,
+            Scoring::Doubling => 2_usize.pow(matches as u32 - 1),
+            Scoring::Linear => matches,
+            Scoring::Fibonacci => (1..matches).fold((1, 1), |(a, b), _| (b, a + b)).0,
+        }
+    }
+}
+
+impl FromStr for Scoring {
+    type Err = String;
+
+    /// Parses `doubling`, `linear`, `fibonacci` or `table:<s0>,<s1>,...`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "doubling" => Ok(Scoring::Doubling),
+            "linear" => Ok(Scoring::Linear),
+            "fibonacci" => Ok(Scoring::Fibonacci),
+            _ => match s.strip_prefix("table:") {
+                Some(table) => table
+                    .split(',')
+                    .map(|n| n.trim().parse::<usize>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Scoring::Table)
+                    .map_err(|e| format!("invalid score table {table:?}: {e}")),
+                None => Err(format!("unknown scoring {s:?}")),
+            },
         }
-        2_usize.pow(num_wins as u32 - 1)
     }
 }
 
+/// What a single card contributed under a given scoring rule.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct CardReport {
+    id: usize,
+    matches: Vec<u32>,
+    score: usize,
+    /// Copies of this card held once the cascade has finished.
+    copies: u128,
+    /// Copies of later cards won by all copies of this card.
+    spawned: u128,
+}
+
 fn parse_input(input: &str) -> Vec<Card> {
     let mut cards = Vec::new();
 
@@ -58,15 +124,69 @@ fn parse_input(input: &str) -> Vec<Card> {
     cards
 }
 
-fn make_pass(input: &Vec<Card>, cards: &BTreeMap<usize, Card>) -> Vec<Card> {
-    let mut output = Vec::new();
-    for card in input {
-        let win_count = card.winner();
-        for j in card.id + 1..card.id + win_count + 1 {
-            output.push(cards.get(&j).unwrap().clone());
-        }
-    }
-    output
+/// Number of copies of every card once the cascade has finished, keyed by id.
+///
+/// Cards are visited in id order, and each card's wins add its own copy count
+/// to the following cards through a difference map, so the counts are found
+/// in a single pass regardless of how many copies are spawned.
+///
+/// Counts can double with every card, so they are kept as `u128` and checked
+/// rather than allowed to wrap.
+fn copies(cards: &BTreeMap<usize, Card>) -> Result<BTreeMap<usize, u128>, String> {
+    // Copies gained and copies expiring at each id.
+    let mut pending: BTreeMap<usize, (u128, u128)> = BTreeMap::new();
+    let mut extra: u128 = 0;
+    let overflow = |id: usize| format!("the number of copies of card {id} overflows a u128");
+
+    cards
+        .iter()
+        .map(|(&id, card)| {
+            while let Some(entry) = pending.first_entry() {
+                if *entry.key() > id {
+                    break;
+                }
+                let (gained, expired) = entry.remove();
+                extra = extra
+                    .checked_add(gained)
+                    .map(|extra| extra - expired)
+                    .ok_or_else(|| overflow(id))?;
+            }
+
+            let count = extra.checked_add(1).ok_or_else(|| overflow(id))?;
+            let win_count = card.winner();
+            if win_count > 0 {
+                let gained = &mut pending.entry(id + 1).or_default().0;
+                *gained = gained.checked_add(count).ok_or_else(|| overflow(id + 1))?;
+                let expired = &mut pending.entry(id + win_count + 1).or_default().1;
+                *expired = expired
+                    .checked_add(count)
+                    .ok_or_else(|| overflow(id + win_count + 1))?;
+            }
+
+            Ok((id, count))
+        })
+        .collect()
+}
+
+fn report(cards: &BTreeMap<usize, Card>, scoring: &Scoring) -> Result<Vec<CardReport>, String> {
+    let copies = copies(cards)?;
+
+    cards
+        .values()
+        .map(|card| {
+            let matches = card.matches();
+            let spawned = copies[&card.id]
+                .checked_mul(matches.len() as u128)
+                .ok_or_else(|| format!("the copies spawned by card {} overflow a u128", card.id))?;
+            Ok(CardReport {
+                id: card.id,
+                score: scoring.score(matches.len()),
+                copies: copies[&card.id],
+                spawned,
+                matches,
+            })
+        })
+        .collect()
 }
 
 fn part_1(input: &str) -> usize {
@@ -76,29 +196,54 @@ fn part_1(input: &str) -> usize {
     total
 }
 
-fn part_2(input: &str) -> usize {
-    let mut cards = parse_input(input);
-    let cards_by_id = BTreeMap::from_iter(cards.iter().map(|c| (c.id, c.clone())));
+fn part_2(input: &str) -> Result<u128, String> {
+    let cards = parse_input(input);
+    let cards_by_id = BTreeMap::from_iter(cards.into_iter().map(|c| (c.id, c)));
+
+    copies(&cards_by_id)?
+        .values()
+        .try_fold(0_u128, |total, &count| total.checked_add(count))
+        .ok_or_else(|| "the total number of cards overflows a u128".to_string())
+}
 
-    let mut result = Vec::new();
-    result.append(&mut cards.clone());
+fn print_report(input: &str, scoring: &Scoring) -> Result<(), String> {
+    let cards_by_id = BTreeMap::from_iter(parse_input(input).into_iter().map(|c| (c.id, c)));
+    let report = report(&cards_by_id, scoring)?;
 
-    loop {
-        let pass = make_pass(&cards, &cards_by_id);
-        if pass.is_empty() {
-            break;
-        }
-        result.append(&mut pass.clone());
-        cards = pass;
+    for card in &report {
+        println!(
+            "Card {}: matches {:?}, score {}, copies {}, spawns {}",
+            card.id, card.matches, card.score, card.copies, card.spawned
+        );
     }
-
-    result.len()
+    println!(
+        "Total score: {}",
+        report.iter().map(|c| c.score).sum::<usize>()
+    );
+    Ok(())
 }
 
 fn main() {
     let input = include_str!("../input.txt");
+
+    let mut args = std::env::args().skip_while(|arg| arg != "--report");
+    if args.next().is_some() {
+        let scoring: Result<Scoring, String> =
+            args.next().map_or(Ok(Scoring::default()), |s| s.parse());
+        if let Err(e) = scoring.and_then(|scoring| print_report(input, &scoring)) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
     println!("Part 1: {}", part_1(input));
-    println!("Part 2: {}", part_2(input));
+    match part_2(input) {
+        Ok(total) => println!("Part 2: {total}"),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -127,6 +272,46 @@ mod test {
         assert_eq!(card.value(), 8);
     }
 
+    #[test]
+    fn test_scoring() {
+        let scores = |scoring: Scoring| (0..=6).map(|n| scoring.score(n)).collect::<Vec<_>>();
+
+        assert_eq!(scores(Scoring::Doubling), vec![0, 1, 2, 4, 8, 16, 32]);
+        assert_eq!(scores(Scoring::Linear), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(scores(Scoring::Fibonacci), vec![0, 1, 1, 2, 3, 5, 8]);
+        assert_eq!(
+            scores("table:0,10,20".parse().unwrap()),
+            vec![0, 10, 20, 20, 20, 20, 20]
+        );
+        assert_eq!(
+            scores("table:5,10".parse().unwrap()),
+            vec![5, 10, 10, 10, 10, 10, 10]
+        );
+        assert!("table:1,x".parse::<Scoring>().is_err());
+        assert!("quadratic".parse::<Scoring>().is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let input = include_str!("../test-input.txt");
+        let cards_by_id = BTreeMap::from_iter(parse_input(input).into_iter().map(|c| (c.id, c)));
+        let report = report(&cards_by_id, &Scoring::Linear).unwrap();
+
+        assert_eq!(
+            report[0],
+            CardReport {
+                id: 1,
+                matches: vec![48, 83, 86, 17],
+                score: 4,
+                copies: 1,
+                spawned: 4,
+            }
+        );
+        assert_eq!(report[2].copies, 4);
+        assert_eq!(report[2].spawned, 8);
+        assert_eq!(report.iter().map(|c| c.score).sum::<usize>(), 4 + 2 + 2 + 1);
+    }
+
     #[test]
     fn test_part_1() {
         let input = include_str!("../test-input.txt");
@@ -134,10 +319,65 @@ mod test {
         assert_eq!(part_1(input), 13);
     }
 
+    #[test]
+    fn test_copies() {
+        let input = include_str!("../test-input.txt");
+        let cards_by_id = BTreeMap::from_iter(parse_input(input).into_iter().map(|c| (c.id, c)));
+
+        assert_eq!(
+            copies(&cards_by_id)
+                .unwrap()
+                .into_values()
+                .collect::<Vec<_>>(),
+            vec![1, 2, 4, 8, 14, 1]
+        );
+    }
+
+    #[test]
+    fn test_copies_many_cards() {
+        let cards_by_id = BTreeMap::from_iter((1..=1_000_000).map(|id| {
+            (
+                id,
+                Card {
+                    id,
+                    winning_numbers: vec![1],
+                    game_numbers: vec![1],
+                },
+            )
+        }));
+
+        let copies = copies(&cards_by_id).unwrap();
+        assert_eq!(copies[&1], 1);
+        assert_eq!(copies[&1_000_000], 1_000_000);
+    }
+
+    #[test]
+    fn test_copies_overflow() {
+        // Every card wins a copy of each of the next two, so card `id` ends
+        // up with F(id + 2) - 1 copies, which passes u128::MAX before card 190.
+        let cards_by_id = BTreeMap::from_iter((1..=200).map(|id| {
+            (
+                id,
+                Card {
+                    id,
+                    winning_numbers: vec![1, 2],
+                    game_numbers: vec![1, 2],
+                },
+            )
+        }));
+
+        let error = copies(&cards_by_id).unwrap_err();
+        assert!(error.contains("overflows a u128"), "{error}");
+
+        let cards_by_id: BTreeMap<_, _> = cards_by_id.into_iter().take(100).collect();
+        let copies = copies(&cards_by_id).unwrap();
+        assert_eq!(copies[&100], 927_372_692_193_078_999_175);
+    }
+
     #[test]
     fn test_part_2() {
         let input = include_str!("../test-input.txt");
 
-        assert_eq!(part_2(input), 30);
+        assert_eq!(part_2(input), Ok(30));
     }
 }
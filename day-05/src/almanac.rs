@@ -0,0 +1,364 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Range;
+
+use crate::function::{Entry, Function};
+
+/// One `X-to-Y map:` section, converting `source` values into `target` values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+    pub source: String,
+    pub target: String,
+    pub lines: Vec<String>,
+    /// The lines' entries sorted by destination, each with the index of the
+    /// line it came from, for reverse lookups.
+    by_dest: Vec<(Entry, usize)>,
+    /// The furthest destination end among `by_dest[..=i]`, so a reverse
+    /// lookup knows when no earlier entry can reach its value any more.
+    reach: Vec<usize>,
+    function: Function,
+}
+
+impl Map {
+    /// Parses the map's lines once into a sorted table. Where the source
+    /// ranges of two lines overlap, the earlier line wins.
+    pub fn new(source: &str, target: &str, lines: Vec<String>) -> Self {
+        let mut by_source: Vec<Entry> = Vec::with_capacity(lines.len());
+        let mut by_dest: Vec<(Entry, usize)> = Vec::with_capacity(lines.len());
+
+        for (index, line) in lines.iter().enumerate() {
+            let values: Vec<usize> = line
+                .split_whitespace()
+                .map(|s| s.parse::<usize>().unwrap())
+                .collect();
+            let entry = Entry {
+                dest: values[0],
+                source: values[1],
+                len: values[2],
+            };
+
+            let mut start = entry.source;
+            let mut uncovered = Vec::new();
+            for taken in &by_source {
+                if taken.source_end() <= start || taken.source >= entry.source_end() {
+                    continue;
+                }
+                if taken.source > start {
+                    uncovered.push(start..taken.source);
+                }
+                start = start.max(taken.source_end());
+            }
+            if start < entry.source_end() {
+                uncovered.push(start..entry.source_end());
+            }
+
+            let pieces = uncovered.into_iter().map(|range| Entry {
+                source: range.start,
+                dest: entry.dest + (range.start - entry.source),
+                len: range.len(),
+            });
+            for piece in pieces {
+                by_source.push(piece);
+                by_dest.push((piece, index));
+            }
+            by_source.sort_by_key(|entry| entry.source);
+        }
+
+        by_dest.sort_by_key(|(entry, _)| entry.dest);
+        let reach = by_dest
+            .iter()
+            .scan(0, |reach, (entry, _)| {
+                *reach = (*reach).max(entry.dest_end());
+                Some(*reach)
+            })
+            .collect();
+
+        Self {
+            source: source.to_string(),
+            target: target.to_string(),
+            lines,
+            by_dest,
+            reach,
+            function: Function::from_entries(&by_source),
+        }
+    }
+
+    pub fn function(&self) -> &Function {
+        &self.function
+    }
+
+    pub fn lookup(&self, source: usize) -> usize {
+        self.function.lookup(source)
+    }
+
+    /// The source mapped onto `dest` by the first of the map's lines that
+    /// produces it, or `dest` itself if no line does.
+    ///
+    /// Destination ranges may overlap, so every entry starting at or before
+    /// `dest` is a candidate until `reach` shows none of the rest get that far.
+    #[allow(dead_code)]
+    pub fn rev_lookup(&self, dest: usize) -> usize {
+        let end = self
+            .by_dest
+            .partition_point(|(entry, _)| entry.dest <= dest);
+        (0..end)
+            .rev()
+            .take_while(|&i| self.reach[i] > dest)
+            .map(|i| &self.by_dest[i])
+            .filter(|(entry, _)| dest < entry.dest_end())
+            .min_by_key(|&&(_, index)| index)
+            .map_or(dest, |(entry, _)| entry.source + (dest - entry.dest))
+    }
+}
+
+/// The seeds and every map of an almanac, forming a graph of categories with
+/// one edge per map.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Almanac {
+    pub seeds: Vec<usize>,
+    pub maps: Vec<Map>,
+}
+
+impl Almanac {
+    pub fn parse(input: &str) -> Self {
+        let mut almanac = Almanac::default();
+
+        // Sections are separated by blank lines, found line by line so that
+        // CRLF line endings split the same way.
+        let lines: Vec<&str> = input.lines().map(str::trim_end).collect();
+        for section in lines.split(|line| line.is_empty()) {
+            if section.is_empty() {
+                continue;
+            }
+            let section = section.join("\n");
+            let (header, body) = section.split_once(':').unwrap();
+
+            match header.trim().strip_suffix(" map") {
+                Some(name) => {
+                    let (source, target) = name.split_once("-to-").unwrap();
+                    almanac.maps.push(Map::new(
+                        source,
+                        target,
+                        body.lines()
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty())
+                            .map(String::from)
+                            .collect(),
+                    ));
+                }
+                None => {
+                    almanac.seeds = body
+                        .split_whitespace()
+                        .map(|s| s.parse::<usize>().unwrap())
+                        .collect()
+                }
+            }
+        }
+
+        almanac
+    }
+
+    /// The shortest chain of maps leading from `source` to `target`, or `None`
+    /// if `target` cannot be reached.
+    pub fn path(&self, source: &str, target: &str) -> Option<Vec<&Map>> {
+        let mut previous: BTreeMap<&str, Option<&Map>> = BTreeMap::from([(source, None)]);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == target {
+                let mut path = Vec::new();
+                let mut current = category;
+                while let Some(Some(map)) = previous.get(current) {
+                    path.push(*map);
+                    current = &map.source;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for map in self.maps.iter().filter(|map| map.source == category) {
+                if !previous.contains_key(map.target.as_str()) {
+                    previous.insert(&map.target, Some(map));
+                    queue.push_back(&map.target);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The maps from `source` to `target` composed into a single function.
+    pub fn function(&self, source: &str, target: &str) -> Option<Function> {
+        let path = self.path(source, target)?;
+        Some(path.iter().fold(Function::identity(), |function, map| {
+            function.then(map.function())
+        }))
+    }
+
+    pub fn resolve(&self, source: &str, target: &str, value: usize) -> Option<usize> {
+        let path = self.path(source, target)?;
+        Some(path.iter().fold(value, |value, map| map.lookup(value)))
+    }
+
+    #[allow(dead_code)]
+    pub fn rev_resolve(&self, source: &str, target: &str, value: usize) -> Option<usize> {
+        let path = self.path(source, target)?;
+        Some(
+            path.iter()
+                .rev()
+                .fold(value, |value, map| map.rev_lookup(value)),
+        )
+    }
+
+    pub fn resolve_ranges(
+        &self,
+        source: &str,
+        target: &str,
+        ranges: Vec<Range<usize>>,
+    ) -> Option<Vec<Range<usize>>> {
+        Some(self.function(source, target)?.lookup_ranges(ranges))
+    }
+
+    /// The exact set of `source` ranges whose values end up in `ranges` of
+    /// the `target` category.
+    pub fn rev_resolve_ranges(
+        &self,
+        source: &str,
+        target: &str,
+        ranges: &[Range<usize>],
+    ) -> Option<Vec<Range<usize>>> {
+        Some(self.function(source, target)?.preimage(ranges))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REORDERED: &str = "seeds: 1 5
+
+water-to-sunlight map:
+100 0 10
+
+seed-to-soil map:
+3 0 5
+
+soil-to-water map:
+0 3 2
+";
+
+    #[test]
+    fn test_parse() {
+        let almanac = Almanac::parse(include_str!("../test-input.txt"));
+
+        assert_eq!(almanac.seeds, vec![79, 14, 55, 13]);
+        assert_eq!(almanac.maps.len(), 7);
+        assert_eq!(
+            almanac.path("seed", "soil").unwrap()[0].lines,
+            vec!["50 98 2", "52 50 48"]
+        );
+    }
+
+    #[test]
+    fn test_parse_crlf() {
+        let input = include_str!("../test-input.txt");
+        let crlf = input.replace('\n', "\r\n");
+
+        assert_eq!(Almanac::parse(&crlf), Almanac::parse(input));
+    }
+
+    #[test]
+    fn test_overlapping_lines() {
+        let map = Map::new(
+            "a",
+            "b",
+            vec!["100 10 10".to_string(), "200 5 10".to_string()],
+        );
+
+        assert_eq!(map.lookup(5), 200);
+        assert_eq!(map.lookup(9), 204);
+        assert_eq!(map.lookup(10), 100);
+        assert_eq!(map.lookup(14), 104);
+        assert_eq!(map.lookup(20), 20);
+        assert_eq!(map.rev_lookup(204), 9);
+        assert_eq!(map.rev_lookup(205), 205);
+    }
+
+    #[test]
+    fn test_overlapping_destinations() {
+        let map = Map::new(
+            "a",
+            "b",
+            vec![
+                "0 200 100".to_string(),
+                "10 400 5".to_string(),
+                "40 500 5".to_string(),
+            ],
+        );
+
+        assert_eq!(map.rev_lookup(50), 250);
+        assert_eq!(map.rev_lookup(12), 212);
+        assert_eq!(map.rev_lookup(99), 299);
+        assert_eq!(map.rev_lookup(100), 100);
+
+        let map = Map::new(
+            "a",
+            "b",
+            vec!["10 400 5".to_string(), "0 200 100".to_string()],
+        );
+        assert_eq!(map.rev_lookup(12), 402);
+        assert_eq!(map.rev_lookup(50), 250);
+    }
+
+    #[test]
+    fn test_function() {
+        let almanac = Almanac::parse(include_str!("../test-input.txt"));
+        let function = almanac.function("seed", "location").unwrap();
+
+        for seed in 0..200 {
+            assert_eq!(
+                function.lookup(seed),
+                almanac.resolve("seed", "location", seed).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_path() {
+        let almanac = Almanac::parse(include_str!("../test-input.txt"));
+
+        let names = |source, target| {
+            almanac.path(source, target).map(|path| {
+                path.iter()
+                    .map(|map| map.target.as_str())
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(
+            names("seed", "water"),
+            Some(vec!["soil", "fertilizer", "water"])
+        );
+        assert_eq!(
+            names("light", "location"),
+            Some(vec!["temperature", "humidity", "location"])
+        );
+        assert_eq!(names("seed", "seed"), Some(vec![]));
+        assert_eq!(names("location", "seed"), None);
+    }
+
+    #[test]
+    fn test_reordered_categories() {
+        let almanac = Almanac::parse(REORDERED);
+
+        assert_eq!(almanac.seeds, vec![1, 5]);
+        assert_eq!(almanac.resolve("seed", "water", 1), Some(1));
+        assert_eq!(almanac.resolve("seed", "sunlight", 1), Some(101));
+        assert_eq!(almanac.resolve("seed", "sunlight", 5), Some(105));
+        assert_eq!(almanac.rev_resolve("seed", "sunlight", 101), Some(1));
+        assert_eq!(almanac.resolve("seed", "location", 1), None);
+        assert_eq!(almanac.function("seed", "sunlight").unwrap().lookup(1), 101);
+        assert_eq!(
+            almanac.rev_resolve_ranges("seed", "sunlight", &[100..103, 103..106]),
+            Some(vec![0..3, 5..6, 100..106])
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use miette::{NamedSource, SourceSpan};
+
+use crate::error::AocError;
+
+/// A map line together with where it sits in the input.
+struct Line {
+    source: usize,
+    len: usize,
+    span: SourceSpan,
+}
+
+impl Line {
+    fn end(&self) -> usize {
+        self.source + self.len
+    }
+}
+
+/// Checks every map of the almanac in `input` for malformed lines, ranges
+/// overflowing `usize`, duplicate or overlapping source ranges and gaps
+/// between source ranges.
+///
+/// Gaps are reported with warning severity since the puzzle defines values
+/// outside every range to map to themselves; everything else is an error.
+pub fn check(name: &str, input: &str) -> Vec<AocError> {
+    let src = || NamedSource::new(name, input.to_string());
+    let mut problems = Vec::new();
+    let mut maps: Vec<(String, Vec<Line>)> = Vec::new();
+
+    let mut offset = 0;
+    for raw in input.split_inclusive('\n') {
+        let text = raw.trim_end();
+        let span = SourceSpan::from((offset, text.len()));
+        offset += raw.len();
+
+        if let Some(header) = text.strip_suffix(" map:") {
+            maps.push((header.to_string(), Vec::new()));
+            continue;
+        }
+        let Some((map, lines)) = maps.last_mut() else {
+            continue;
+        };
+        if text.is_empty() {
+            continue;
+        }
+
+        let values: Option<Vec<usize>> = text
+            .split_whitespace()
+            .map(|s| s.parse::<usize>().ok())
+            .collect();
+        let Some(&[dest, source, len]) = values.as_deref() else {
+            problems.push(AocError::MalformedLine {
+                map: map.clone(),
+                src: src(),
+                line: span,
+            });
+            continue;
+        };
+
+        if source.checked_add(len).is_none() || dest.checked_add(len).is_none() {
+            problems.push(AocError::RangeOverflow {
+                map: map.clone(),
+                src: src(),
+                line: span,
+            });
+            continue;
+        }
+
+        lines.push(Line { source, len, span });
+    }
+
+    for (map, mut lines) in maps {
+        lines.retain(|line| line.len > 0);
+
+        for (i, first) in lines.iter().enumerate() {
+            for second in &lines[i + 1..] {
+                if first.source == second.source && first.len == second.len {
+                    problems.push(AocError::DuplicateRange {
+                        map: map.clone(),
+                        src: src(),
+                        first: first.span,
+                        second: second.span,
+                    });
+                } else if first.source < second.end() && second.source < first.end() {
+                    problems.push(AocError::OverlappingRanges {
+                        map: map.clone(),
+                        src: src(),
+                        first: first.span,
+                        second: second.span,
+                    });
+                }
+            }
+        }
+
+        lines.sort_by_key(|line| line.source);
+        let mut furthest: Option<&Line> = None;
+        for line in &lines {
+            if let Some(before) = furthest {
+                if before.end() < line.source {
+                    problems.push(AocError::RangeGap {
+                        map: map.clone(),
+                        len: line.source - before.end(),
+                        src: src(),
+                        before: before.span,
+                        after: line.span,
+                    });
+                }
+            }
+            if furthest.is_none_or(|before| line.end() > before.end()) {
+                furthest = Some(line);
+            }
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(input: &str) -> Vec<String> {
+        use miette::Diagnostic;
+
+        check("test", input)
+            .iter()
+            .map(|problem| problem.code().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_check_clean() {
+        assert_eq!(
+            codes("seeds: 1\n\na-to-b map:\n10 0 5\n20 5 5\n"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_check_problems() {
+        let input = "seeds: 1
+
+a-to-b map:
+10 0 5
+20 3 5
+30 0 5
+1 2
+
+b-to-c map:
+0 18446744073709551615 2
+0 0 5
+0 10 5
+";
+
+        assert_eq!(
+            codes(input),
+            vec![
+                "aoc::malformed_line",
+                "aoc::range_overflow",
+                "aoc::overlapping_ranges",
+                "aoc::duplicate_range",
+                "aoc::overlapping_ranges",
+                "aoc::range_gap",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_labels_line() {
+        let input = "seeds: 1\n\na-to-b map:\n10 0 5\nbad\n";
+        let problems = check("test", input);
+
+        let AocError::MalformedLine { line, .. } = &problems[0] else {
+            panic!("expected a malformed line, got {:?}", problems[0]);
+        };
+        assert_eq!(&input[line.offset()..line.offset() + line.len()], "bad");
+    }
+}
@@ -0,0 +1,75 @@
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use thiserror::Error;
+
+#[derive(Error, Diagnostic, Debug)]
+pub enum AocError {
+    #[error("malformed line in {map} map")]
+    #[diagnostic(
+        code(aoc::malformed_line),
+        help("map lines are three numbers: destination start, source start and length")
+    )]
+    MalformedLine {
+        map: String,
+        #[source_code]
+        src: NamedSource,
+        #[label("expected three numbers")]
+        line: SourceSpan,
+    },
+
+    #[error("range overflows usize in {map} map")]
+    #[diagnostic(code(aoc::range_overflow))]
+    RangeOverflow {
+        map: String,
+        #[source_code]
+        src: NamedSource,
+        #[label("start + length does not fit in a usize")]
+        line: SourceSpan,
+    },
+
+    #[error("duplicate source range in {map} map")]
+    #[diagnostic(
+        code(aoc::duplicate_range),
+        help("only the first of these lines is ever used")
+    )]
+    DuplicateRange {
+        map: String,
+        #[source_code]
+        src: NamedSource,
+        #[label("first defined here")]
+        first: SourceSpan,
+        #[label("defined again here")]
+        second: SourceSpan,
+    },
+
+    #[error("overlapping source ranges in {map} map")]
+    #[diagnostic(
+        code(aoc::overlapping_ranges),
+        help("values in the overlap resolve to the earlier line")
+    )]
+    OverlappingRanges {
+        map: String,
+        #[source_code]
+        src: NamedSource,
+        #[label("this range")]
+        first: SourceSpan,
+        #[label("overlaps this one")]
+        second: SourceSpan,
+    },
+
+    #[error("gap between source ranges in {map} map")]
+    #[diagnostic(
+        code(aoc::range_gap),
+        severity(Warning),
+        help("the {len} values between these ranges map to themselves")
+    )]
+    RangeGap {
+        map: String,
+        len: usize,
+        #[source_code]
+        src: NamedSource,
+        #[label("this range ends")]
+        before: SourceSpan,
+        #[label("before this one starts")]
+        after: SourceSpan,
+    },
+}
@@ -0,0 +1,278 @@
+use std::cmp::{max, min};
+use std::ops::Range;
+
+/// `len` consecutive values starting at `source` that map onto the values
+/// starting at `dest`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub source: usize,
+    pub dest: usize,
+    pub len: usize,
+}
+
+impl Entry {
+    pub fn source_end(&self) -> usize {
+        self.source.saturating_add(self.len)
+    }
+
+    pub fn dest_end(&self) -> usize {
+        self.dest.saturating_add(self.len)
+    }
+}
+
+/// A piecewise-linear function defined on every `usize`.
+///
+/// The pieces are sorted by source, start at zero and follow on from each
+/// other without gaps, so a value is mapped by binary searching for the piece
+/// containing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pieces: Vec<Entry>,
+}
+
+impl Default for Function {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Function {
+    pub fn identity() -> Self {
+        Self {
+            pieces: vec![Entry {
+                source: 0,
+                dest: 0,
+                len: usize::MAX,
+            }],
+        }
+    }
+
+    /// Builds a function from entries sorted by source that do not overlap.
+    /// Values outside every entry map to themselves.
+    pub fn from_entries(entries: &[Entry]) -> Self {
+        let mut pieces = Vec::with_capacity(entries.len() * 2 + 1);
+        let mut cursor = 0;
+
+        for entry in entries.iter().filter(|entry| entry.len > 0) {
+            if entry.source > cursor {
+                pieces.push(Entry {
+                    source: cursor,
+                    dest: cursor,
+                    len: entry.source - cursor,
+                });
+            }
+            pieces.push(*entry);
+            cursor = entry.source_end();
+        }
+
+        if cursor < usize::MAX {
+            pieces.push(Entry {
+                source: cursor,
+                dest: cursor,
+                len: usize::MAX - cursor,
+            });
+        }
+
+        Self { pieces }.merged()
+    }
+
+    fn index_of(&self, value: usize) -> usize {
+        self.pieces.partition_point(|piece| piece.source <= value) - 1
+    }
+
+    pub fn lookup(&self, value: usize) -> usize {
+        let piece = &self.pieces[self.index_of(value)];
+        piece.dest + (value - piece.source)
+    }
+
+    /// Maps whole ranges, splitting them wherever they cross the boundary
+    /// between two pieces.
+    pub fn lookup_ranges(&self, ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+        let mut result = Vec::new();
+
+        for range in ranges.into_iter().filter(|range| !range.is_empty()) {
+            for piece in &self.pieces[self.index_of(range.start)..] {
+                if piece.source >= range.end {
+                    break;
+                }
+                let start = max(range.start, piece.source);
+                let end = min(range.end, piece.source_end());
+                result.push(piece.dest + (start - piece.source)..piece.dest + (end - piece.source));
+            }
+        }
+
+        result
+    }
+
+    /// Every value mapped into one of `ranges`, as sorted, disjoint ranges.
+    pub fn preimage(&self, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+        let mut result = Vec::new();
+
+        for piece in &self.pieces {
+            for range in ranges {
+                let start = max(range.start, piece.dest);
+                let end = min(range.end, piece.dest_end());
+                if start < end {
+                    result.push(
+                        piece.source + (start - piece.dest)..piece.source + (end - piece.dest),
+                    );
+                }
+            }
+        }
+
+        normalise(result)
+    }
+
+    /// The function applying `self` and then `next`.
+    pub fn then(&self, next: &Function) -> Function {
+        let mut pieces = Vec::with_capacity(self.pieces.len() + next.pieces.len());
+
+        for piece in &self.pieces {
+            let image = piece.dest..piece.dest_end();
+            for inner in &next.pieces[next.index_of(image.start)..] {
+                if inner.source >= image.end {
+                    break;
+                }
+                let start = max(image.start, inner.source);
+                let end = min(image.end, inner.source_end());
+                pieces.push(Entry {
+                    source: piece.source + (start - piece.dest),
+                    dest: inner.dest + (start - inner.source),
+                    len: end - start,
+                });
+            }
+        }
+
+        Self { pieces }.merged()
+    }
+
+    /// Joins neighbouring pieces that continue the same linear segment.
+    fn merged(self) -> Self {
+        let mut pieces: Vec<Entry> = Vec::with_capacity(self.pieces.len());
+
+        for piece in self.pieces {
+            match pieces.last_mut() {
+                Some(last)
+                    if last.dest_end() == piece.dest && last.source_end() == piece.source =>
+                {
+                    last.len += piece.len;
+                }
+                _ => pieces.push(piece),
+            }
+        }
+
+        Self { pieces }
+    }
+}
+
+/// Sorts `ranges` and joins the ones that overlap or touch.
+pub fn normalise(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+
+    let mut result: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match result.last_mut() {
+            Some(last) if range.start <= last.end => last.end = max(last.end, range.end),
+            _ => result.push(range),
+        }
+    }
+    result
+}
+
+/// The values contained in both `a` and `b`, which must be normalised.
+pub fn intersect(a: &[Range<usize>], b: &[Range<usize>]) -> Vec<Range<usize>> {
+    let (mut i, mut j) = (0, 0);
+    let mut result = Vec::new();
+
+    while i < a.len() && j < b.len() {
+        let start = max(a[i].start, b[j].start);
+        let end = min(a[i].end, b[j].end);
+        if start < end {
+            result.push(start..end);
+        }
+        if a[i].end < b[j].end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(entries: &[(usize, usize, usize)]) -> Function {
+        Function::from_entries(
+            &entries
+                .iter()
+                .map(|&(source, dest, len)| Entry { source, dest, len })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_from_entries() {
+        let f = function(&[(50, 52, 48), (98, 50, 2)]);
+
+        assert_eq!(f.pieces.len(), 4);
+        assert_eq!(f.lookup(0), 0);
+        assert_eq!(f.lookup(49), 49);
+        assert_eq!(f.lookup(50), 52);
+        assert_eq!(f.lookup(97), 99);
+        assert_eq!(f.lookup(98), 50);
+        assert_eq!(f.lookup(100), 100);
+        assert_eq!(f.lookup(usize::MAX - 1), usize::MAX - 1);
+    }
+
+    #[test]
+    fn test_then() {
+        let f = function(&[(0, 10, 5)]);
+        let g = function(&[(12, 0, 10)]);
+        let h = f.then(&g);
+
+        for value in 0..30 {
+            assert_eq!(h.lookup(value), g.lookup(f.lookup(value)));
+        }
+        assert_eq!(
+            Function::identity().then(&Function::identity()),
+            Function::identity()
+        );
+    }
+
+    #[test]
+    fn test_preimage() {
+        let f = function(&[(10, 100, 10), (100, 10, 10)]);
+
+        assert_eq!(f.preimage(&[0..5, 12..18]), vec![0..5, 102..108]);
+        assert_eq!(
+            f.preimage(&[95..100, 100..115]),
+            vec![10..20, 95..100, 110..115]
+        );
+        assert_eq!(f.preimage(&[100..104, 106..110]), vec![10..14, 16..20]);
+    }
+
+    #[test]
+    fn test_normalise_and_intersect() {
+        assert_eq!(
+            normalise(vec![5..8, 0..2, 2..3, 7..10, 4..4]),
+            vec![0..3, 5..10]
+        );
+        assert_eq!(
+            intersect(&[0..3, 5..10], &[2..6, 9..20]),
+            vec![2..3, 5..6, 9..10]
+        );
+    }
+
+    #[test]
+    fn test_lookup_ranges() {
+        let f = function(&[(10, 100, 10)]);
+
+        assert_eq!(
+            f.lookup_ranges(vec![5..15, 18..25]),
+            vec![5..10, 100..105, 108..110, 20..25]
+        );
+    }
+}
@@ -1,169 +1,154 @@
-use indicatif::ProgressIterator;
-use std::collections::BTreeMap;
-use std::collections::BinaryHeap;
-
-const SOIL: &str = "seed-to-soil";
-const FERT: &str = "soil-to-fertilizer";
-const WATER: &str = "fertilizer-to-water";
-const LIGHT: &str = "water-to-light";
-const TEMP: &str = "light-to-temperature";
-const HUMIDITY: &str = "temperature-to-humidity";
-const LOC: &str = "humidity-to-location";
-
-fn extract_seeds(input: &str) -> Vec<usize> {
-    let line = input.lines().next().unwrap();
-    let (_, values) = line.split_once(':').unwrap();
-    values
-        .split_whitespace()
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect()
-}
-
-fn extract_map_lines(input: &str, key: &'static str) -> Vec<String> {
-    let mut lines: Vec<String> = input.lines().map(String::from).collect();
-
-    let start_idx = lines.iter().position(|line| line.starts_with(key)).unwrap();
-    lines.drain(..=start_idx);
-
-    if let Some(end_idx) = lines.iter().position(|line| line.is_empty()) {
-        lines.drain(end_idx..);
-    }
-
-    lines
-}
+use std::ops::Range;
 
-fn lookup(maps: &BTreeMap<&'static str, Vec<String>>, source: usize, map: &'static str) -> usize {
-    let map_lines = maps.get(map).unwrap();
+use almanac::Almanac;
 
-    for line in map_lines {
-        let values: Vec<usize> = line
-            .split_whitespace()
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect();
+mod almanac;
+mod check;
+mod error;
+mod function;
 
-        let dest_range_start = values[0];
-        let source_range_start = values[1];
-        let range_length = values[2];
+use function::{intersect, normalise};
+use miette::{Diagnostic, Severity};
 
-        let source_range_end = source_range_start + range_length;
-        if source >= source_range_start && source < source_range_end {
-            return dest_range_start + (source - source_range_start);
-        }
-    }
+const SEED: &str = "seed";
+const LOCATION: &str = "location";
 
-    source
+fn lookup_location_ranges(almanac: &Almanac, seeds: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    almanac.resolve_ranges(SEED, LOCATION, seeds).unwrap()
 }
 
-fn rev_lookup(maps: &BTreeMap<&'static str, Vec<String>>, dest: usize, map: &'static str) -> usize {
-    let map_lines = maps.get(map).unwrap();
-
-    for line in map_lines {
-        let values: Vec<usize> = line
-            .split_whitespace()
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect();
-
-        let dest_range_start = values[0];
-        let source_range_start = values[1];
-        let range_length = values[2];
+fn seed_ranges(almanac: &Almanac) -> Vec<Range<usize>> {
+    almanac
+        .seeds
+        .chunks(2)
+        .map(|chunk| chunk[0]..chunk[0] + chunk[1])
+        .collect()
+}
 
-        let dest_range_end = dest_range_start + range_length;
-        if dest >= dest_range_start && dest < dest_range_end {
-            return source_range_start + (dest - dest_range_start);
+/// The seeds whose locations are among the lowest `fraction` of all the
+/// locations reached from `seeds`.
+fn seeds_in_lowest(
+    almanac: &Almanac,
+    seeds: Vec<Range<usize>>,
+    fraction: f64,
+) -> Vec<Range<usize>> {
+    let seeds = normalise(seeds);
+    let locations = normalise(lookup_location_ranges(almanac, seeds.clone()));
+
+    let total: usize = locations.iter().map(|range| range.len()).sum();
+    let mut quota = (total as f64 * fraction).ceil() as usize;
+
+    let mut lowest = Vec::new();
+    for range in locations {
+        if quota == 0 {
+            break;
         }
+        let len = range.len().min(quota);
+        lowest.push(range.start..range.start + len);
+        quota -= len;
     }
 
-    dest
-}
-
-fn lookup_location(maps: &BTreeMap<&'static str, Vec<String>>, seed: usize) -> usize {
-    let soil = lookup(maps, seed, SOIL);
-
-    let fert = lookup(maps, soil, FERT);
-    let water = lookup(maps, fert, WATER);
-    let light = lookup(maps, water, LIGHT);
-    let temp = lookup(maps, light, TEMP);
-    let humidity = lookup(maps, temp, HUMIDITY);
-    
-
-    lookup(maps, humidity, LOC)
-}
-
-fn rev_lookup_location(maps: &BTreeMap<&'static str, Vec<String>>, loc: usize) -> usize {
-    let humidity = rev_lookup(maps, loc, LOC);
-    let temp = rev_lookup(maps, humidity, HUMIDITY);
-    let light = rev_lookup(maps, temp, TEMP);
-    let water = rev_lookup(maps, light, LIGHT);
-    let fert = rev_lookup(maps, water, WATER);
-    let soil = rev_lookup(maps, fert, FERT);
-    
-
-    rev_lookup(maps, soil, SOIL)
-}
-
-fn extract_maps(input: &str) -> BTreeMap<&'static str, Vec<String>> {
-    let mut maps = BTreeMap::new();
-    maps.insert(SOIL, extract_map_lines(input, SOIL));
-    maps.insert(FERT, extract_map_lines(input, FERT));
-    maps.insert(WATER, extract_map_lines(input, WATER));
-    maps.insert(LIGHT, extract_map_lines(input, LIGHT));
-    maps.insert(TEMP, extract_map_lines(input, TEMP));
-    maps.insert(HUMIDITY, extract_map_lines(input, HUMIDITY));
-    maps.insert(LOC, extract_map_lines(input, LOC));
-    maps
+    let candidates = almanac.rev_resolve_ranges(SEED, LOCATION, &lowest).unwrap();
+    intersect(&seeds, &candidates)
 }
 
 fn part_1(input: &str) -> u32 {
-    let maps = extract_maps(input);
-    let seeds = extract_seeds(input);
-    let mut locs = Vec::new();
-    for seed in seeds {
-        locs.push(lookup_location(&maps, seed));
-    }
-    *locs.iter().min().unwrap() as u32
+    let almanac = Almanac::parse(input);
+    let seed_to_location = almanac.function(SEED, LOCATION).unwrap();
+
+    almanac
+        .seeds
+        .iter()
+        .map(|seed| seed_to_location.lookup(*seed))
+        .min()
+        .unwrap() as u32
 }
 
 fn part_2(input: &str) -> usize {
-    let maps = extract_maps(input);
-    let seed_pairs = extract_seeds(input);
-
-    let mut ranges = Vec::new();
-    for chunk in seed_pairs.chunks(2) {
-        let start = chunk[0];
-        let size = chunk[1];
-        ranges.push(start..start + size);
-    }
-
-    let mut h2l = maps.get(LOC).unwrap().iter().collect::<BinaryHeap<_>>();
-
-    let line = h2l.pop().unwrap();
-    let values: Vec<usize> = line
-        .split_whitespace()
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect();
+    let almanac = Almanac::parse(input);
+
+    let ranges = seed_ranges(&almanac);
+
+    let location = lookup_location_ranges(&almanac, ranges.clone())
+        .into_iter()
+        .map(|range| range.start)
+        .min()
+        .unwrap();
+
+    // Maps need not be one-to-one, so check against every seed reaching the
+    // location rather than the single one a reverse lookup picks.
+    let lowest = location..location + 1;
+    debug_assert!(!intersect(
+        &normalise(ranges),
+        &almanac
+            .rev_resolve_ranges(SEED, LOCATION, &[lowest])
+            .unwrap()
+    )
+    .is_empty());
+
+    location
+}
 
-    let dest_range_start = values[0];
-    let range_length = values[2];
+fn main() {
+    let input = include_str!("../input.txt");
 
-    let rng = 0..dest_range_start + range_length;
+    let args: Vec<String> = std::env::args().collect();
 
-    rng.into_iter()
-        .progress()
-        .find(|loc| {
-            let seed = rev_lookup_location(&maps, *loc);
-            for range in &ranges {
-                if range.contains(&seed) {
-                    return true;
-                }
+    if args.iter().any(|arg| arg == "--check") {
+        let problems = check::check("input.txt", input);
+        let failed = problems
+            .iter()
+            .any(|problem| problem.severity().unwrap_or(Severity::Error) == Severity::Error);
+        for problem in problems {
+            eprintln!("{:?}", miette::Report::new(problem));
+        }
+        if failed {
+            std::process::exit(1);
+        }
+    }
+    if let Some(i) = args.iter().position(|arg| arg == "--resolve") {
+        let (Some(source), Some(target), Some(value)) =
+            (args.get(i + 1), args.get(i + 2), args.get(i + 3))
+        else {
+            eprintln!("usage: --resolve <source> <target> <value>");
+            std::process::exit(1);
+        };
+        let value: usize = match value.parse() {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("invalid value {value:?}: {e}");
+                std::process::exit(1);
             }
-            false
-        })
-        .unwrap()
-    //    0
-}
+        };
+        match Almanac::parse(input).resolve(source, target, value) {
+            Some(result) => println!("{source} {value} -> {target} {result}"),
+            None => eprintln!("no path from {source} to {target}"),
+        }
+        return;
+    }
+    if let Some(i) = args.iter().position(|arg| arg == "--lowest") {
+        let Some(percent) = args.get(i + 1) else {
+            eprintln!("usage: --lowest <percent>");
+            std::process::exit(1);
+        };
+        let percent: f64 = match percent.parse() {
+            Ok(percent) if (0.0..=100.0).contains(&percent) => percent,
+            Ok(_) => {
+                eprintln!("percentage {percent:?} is not between 0 and 100");
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("invalid percentage {percent:?}: {e}");
+                std::process::exit(1);
+            }
+        };
+        let almanac = Almanac::parse(input);
+        for range in seeds_in_lowest(&almanac, seed_ranges(&almanac), percent / 100.0) {
+            println!("{}..{}", range.start, range.end);
+        }
+        return;
+    }
 
-fn main() {
-    let input = include_str!("../input.txt");
     println!("Part 1: {}", part_1(input));
     println!("Part 2: {}", part_2(input));
 }
@@ -172,50 +157,97 @@ fn main() {
 mod tests {
     use super::*;
 
+    fn rev_lookup_location(almanac: &Almanac, loc: usize) -> usize {
+        almanac.rev_resolve(SEED, LOCATION, loc).unwrap()
+    }
+
+    fn lookup_location(almanac: &Almanac, seed: usize) -> usize {
+        almanac.resolve(SEED, LOCATION, seed).unwrap()
+    }
+
     #[test]
-    fn test_extract_seeds() {
+    fn test_lookup_location() {
         let input = include_str!("../test-input.txt");
-        let seeds = extract_seeds(input);
-        assert_eq!(seeds, vec![79, 14, 55, 13]);
+        let almanac = Almanac::parse(input);
+
+        assert_eq!(lookup_location(&almanac, 79), 82);
+        assert_eq!(lookup_location(&almanac, 14), 43);
+        assert_eq!(lookup_location(&almanac, 55), 86);
+        assert_eq!(lookup_location(&almanac, 13), 35);
     }
 
     #[test]
-    fn test_extract_map() {
+    fn test_reverse_lookup() {
         let input = include_str!("../test-input.txt");
-        let lines = extract_map_lines(input, "seed-to-soil");
-        assert_eq!(lines, vec!["50 98 2", "52 50 48"]);
+        let almanac = Almanac::parse(input);
+
+        assert_eq!(rev_lookup_location(&almanac, 82), 79);
+        assert_eq!(rev_lookup_location(&almanac, 43), 14);
+        assert_eq!(rev_lookup_location(&almanac, 86), 55);
+        assert_eq!(rev_lookup_location(&almanac, 35), 13);
     }
 
     #[test]
-    fn test_lookup_location() {
+    fn test_lookup() {
         let input = include_str!("../test-input.txt");
-        let maps = extract_maps(input);
+        let almanac = Almanac::parse(input);
+        let soil = almanac.path("seed", "soil").unwrap()[0];
 
-        assert_eq!(lookup_location(&maps, 79), 82);
-        assert_eq!(lookup_location(&maps, 14), 43);
-        assert_eq!(lookup_location(&maps, 55), 86);
-        assert_eq!(lookup_location(&maps, 13), 35);
+        assert_eq!(soil.lookup(79), 81);
+        assert_eq!(soil.lookup(14), 14);
+        assert_eq!(soil.lookup(55), 57);
+        assert_eq!(soil.lookup(13), 13);
     }
 
     #[test]
-    fn test_reverse_lookup() {
+    fn test_lookup_ranges() {
         let input = include_str!("../test-input.txt");
-        let maps = extract_maps(input);
+        let almanac = Almanac::parse(input);
+        let soil = almanac.path("seed", "soil").unwrap()[0];
 
-        assert_eq!(rev_lookup_location(&maps, 82), 79);
-        assert_eq!(rev_lookup_location(&maps, 43), 14);
-        assert_eq!(rev_lookup_location(&maps, 86), 55);
-        assert_eq!(rev_lookup_location(&maps, 35), 13);
+        let mut ranges = soil.function().lookup_ranges(vec![45..55, 97..101]);
+        ranges.sort_by_key(|range| range.start);
+        assert_eq!(ranges, vec![45..50, 50..52, 52..57, 99..100, 100..101]);
     }
 
     #[test]
-    fn test_lookup() {
+    fn test_lookup_location_ranges() {
         let input = include_str!("../test-input.txt");
-        let maps = extract_maps(input);
-        assert_eq!(lookup(&maps, 79, SOIL), 81);
-        assert_eq!(lookup(&maps, 14, SOIL), 14);
-        assert_eq!(lookup(&maps, 55, SOIL), 57);
-        assert_eq!(lookup(&maps, 13, SOIL), 13);
+        let almanac = Almanac::parse(input);
+
+        let ranges = lookup_location_ranges(&almanac, vec![79..93, 55..68]);
+        assert_eq!(ranges.iter().map(|range| range.len()).sum::<usize>(), 27);
+        assert_eq!(ranges.iter().map(|range| range.start).min(), Some(46));
+        for seed in (79..93).chain(55..68) {
+            let location = lookup_location(&almanac, seed);
+            assert!(ranges.iter().any(|range| range.contains(&location)));
+        }
+    }
+
+    #[test]
+    fn test_seeds_in_lowest() {
+        let input = include_str!("../test-input.txt");
+        let almanac = Almanac::parse(input);
+
+        assert_eq!(
+            seeds_in_lowest(&almanac, seed_ranges(&almanac), 0.0),
+            vec![]
+        );
+        assert_eq!(
+            seeds_in_lowest(&almanac, seed_ranges(&almanac), 0.01),
+            vec![82..83]
+        );
+        assert_eq!(
+            seeds_in_lowest(&almanac, seed_ranges(&almanac), 1.0),
+            vec![55..68, 79..93]
+        );
+
+        for seed in seeds_in_lowest(&almanac, seed_ranges(&almanac), 0.25)
+            .into_iter()
+            .flatten()
+        {
+            assert!(lookup_location(&almanac, seed) < 60);
+        }
     }
 
     #[test]
@@ -229,4 +261,21 @@ mod tests {
         let input = include_str!("../test-input.txt");
         assert_eq!(part_2(input), 46);
     }
+
+    #[test]
+    fn test_part_2_many_to_one() {
+        // Seeds 25 and 58 both end up at location 25, and the reverse lookup
+        // only finds 58.
+        let input = "seeds: 25 1
+
+seed-to-a map:
+35 8 17
+39 55 18
+
+a-to-location map:
+11 44 18
+";
+        assert_eq!(part_2(input), 25);
+        assert_eq!(rev_lookup_location(&Almanac::parse(input), 25), 58);
+    }
 }
@@ -1,76 +1,148 @@
 use nom::{
     bytes::complete::is_not,
-    character::complete::{self, line_ending, space1},
+    character::complete::{digit1, line_ending, space1},
     multi::separated_list1,
     sequence::separated_pair,
     IResult, Parser as _,
 };
 use nom_supreme::ParserExt as _;
+use thiserror::Error;
 use tracing::instrument;
 
+#[derive(Debug, Error, Eq, PartialEq)]
+enum RaceError {
+    #[error("could not parse race sheet: {0}")]
+    Parse(String),
+    #[error("{value} is too large, race times and records must fit in a u128")]
+    TooLarge { value: String },
+    #[error("the number of ways to win overflows a u128")]
+    Overflow,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct Race {
-    time: u64,
-    record: u64,
+    time: u128,
+    record: u128,
 }
 
 impl Race {
-    fn distance(&self) -> Vec<u64> {
-        (0..self.time)
-            .map(|t| t * (self.time - t))
-            .collect::<Vec<u64>>()
+    /// Whether holding the button for `hold` beats the record. A distance too
+    /// large for a u128 beats any record.
+    fn wins(&self, hold: u128) -> bool {
+        hold.checked_mul(self.time - hold)
+            .is_none_or(|distance| distance > self.record)
+    }
+
+    /// Number of hold times whose distance strictly beats the record.
+    ///
+    /// The winning hold times form the window between the roots of
+    /// `t * (time - t) = record`, which is symmetric around `time / 2`. The
+    /// lower root is estimated with an exact integer square root and then
+    /// nudged onto the first hold time that wins. When the discriminant does
+    /// not fit in a u128 the lower root is binary searched instead.
+    fn ways_to_win(&self) -> u128 {
+        let half = self.time / 2;
+
+        let discriminant = self
+            .time
+            .checked_mul(self.time)
+            .zip(self.record.checked_mul(4))
+            .map(|(square, record)| square.checked_sub(record));
+        let mut low = match discriminant {
+            Some(None) => return 0,
+            Some(Some(discriminant)) => (self.time - isqrt(discriminant)) / 2,
+            None => self.first_win_search(),
+        };
+
+        while low > 0 && self.wins(low - 1) {
+            low -= 1;
+        }
+        while low <= half && !self.wins(low) {
+            low += 1;
+        }
+
+        if low > half {
+            0
+        } else {
+            self.time - 2 * low + 1
+        }
+    }
+
+    /// The first hold time that wins, or `time / 2 + 1` if none does. Below
+    /// `time / 2` the distance only grows, so the winners are a suffix.
+    fn first_win_search(&self) -> u128 {
+        let (mut low, mut high) = (0, self.time / 2 + 1);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.wins(mid) {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        low
     }
 }
 
-fn nums(input: &str) -> IResult<&str, Vec<u64>> {
+/// The largest integer whose square is at most `n`.
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    let mut x = 1 << ((128 - n.leading_zeros()).div_ceil(2));
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+fn nums(input: &str) -> IResult<&str, Vec<&str>> {
     is_not("0123456789")
-        .precedes(separated_list1(space1, complete::u64))
+        .precedes(separated_list1(space1, digit1))
         .parse(input)
 }
 
-fn parse_times(input: &str) -> IResult<&str, Vec<Race>> {
-    let (input, (times, records)) = separated_pair(nums, line_ending, nums).parse(input)?;
-
-    let races = itertools::izip!(&times, &records)
-        .map(|(&time, &record)| Race { time, record })
-        .collect();
+fn parse_times(input: &str) -> Result<Vec<Race>, RaceError> {
+    let (_, (times, records)) = separated_pair(nums, line_ending, nums)
+        .parse(input)
+        .map_err(|e| RaceError::Parse(e.to_string()))?;
 
-    Ok((input, races))
-}
+    let number = |value: &str| {
+        value.parse::<u128>().map_err(|_| RaceError::TooLarge {
+            value: value.to_string(),
+        })
+    };
 
-#[instrument]
-fn part_1(input: &str) -> usize {
-    let (_, races) = parse_times(input).unwrap();
-
-    let results = races
-        .iter()
-        .map(|r| {
-            r.distance()
-                .into_iter()
-                .filter(|&d| d > r.record)
-                .collect::<Vec<u64>>()
+    itertools::izip!(times, records)
+        .map(|(time, record)| {
+            Ok(Race {
+                time: number(time)?,
+                record: number(record)?,
+            })
         })
-        .collect::<Vec<Vec<u64>>>();
+        .collect()
+}
 
-    results.iter().map(Vec::len).product()
+fn product(races: &[Race]) -> Result<u128, RaceError> {
+    races.iter().try_fold(1u128, |product, race| {
+        product
+            .checked_mul(race.ways_to_win())
+            .ok_or(RaceError::Overflow)
+    })
 }
 
 #[instrument]
-fn part_2(input: &str) -> usize {
-    let input = part2_preformat(input);
-    let (_, races) = parse_times(&input).unwrap();
-
-    let results = races
-        .iter()
-        .map(|r| {
-            r.distance()
-                .into_iter()
-                .filter(|&d| d > r.record)
-                .collect::<Vec<u64>>()
-        })
-        .collect::<Vec<Vec<u64>>>();
+fn part_1(input: &str) -> Result<u128, RaceError> {
+    product(&parse_times(input)?)
+}
 
-    results.iter().map(Vec::len).product()
+#[instrument]
+fn part_2(input: &str) -> Result<u128, RaceError> {
+    product(&parse_times(&part2_preformat(input))?)
 }
 
 fn part2_preformat(input: &str) -> String {
@@ -83,25 +155,128 @@ fn part2_preformat(input: &str) -> String {
         .join("\n")
 }
 
-fn main() {
+fn main() -> Result<(), RaceError> {
     let input = include_str!("../input.txt");
-    println!("Part 1: {}", part_1(input));
-    println!("Part 2: {}", part_2(input));
+    println!("Part 1: {}", part_1(input)?);
+    println!("Part 2: {}", part_2(input)?);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn brute_force(race: &Race) -> u128 {
+        (0..race.time).filter(|&t| race.wins(t)).count() as u128
+    }
+
+    #[test_log::test]
+    fn test_isqrt() {
+        for n in 0..10_000u128 {
+            let root = isqrt(n);
+            assert!(root * root <= n && (root + 1) * (root + 1) > n);
+        }
+        assert_eq!(isqrt(u64::MAX as u128 * u64::MAX as u128), u64::MAX as u128);
+    }
+
+    #[test_log::test]
+    fn test_ways_to_win() {
+        assert_eq!(Race { time: 7, record: 9 }.ways_to_win(), 4);
+        assert_eq!(
+            Race {
+                time: 15,
+                record: 40
+            }
+            .ways_to_win(),
+            8
+        );
+        // The roots 10 and 20 exactly tie the record, so they do not count.
+        assert_eq!(
+            Race {
+                time: 30,
+                record: 200
+            }
+            .ways_to_win(),
+            9
+        );
+        assert_eq!(
+            Race {
+                time: 10,
+                record: 25
+            }
+            .ways_to_win(),
+            0
+        );
+        assert_eq!(
+            Race {
+                time: 10,
+                record: 24
+            }
+            .ways_to_win(),
+            1
+        );
+        assert_eq!(Race { time: 0, record: 0 }.ways_to_win(), 0);
+
+        for time in 0..60 {
+            for record in 0..time * time / 4 + 2 {
+                let race = Race { time, record };
+                assert_eq!(race.ways_to_win(), brute_force(&race), "{race:?}");
+            }
+        }
+    }
+
     #[test_log::test]
     fn test_part_1() {
         let input = include_str!("../test-input.txt");
-        assert_eq!(part_1(input), 288);
+        assert_eq!(part_1(input), Ok(288));
     }
 
     #[test_log::test]
     fn test_part_2() {
         let input = include_str!("../test-input.txt");
-        assert_eq!(part_2(input), 71503);
+        assert_eq!(part_2(input), Ok(71503));
+    }
+
+    #[test_log::test]
+    fn test_large_races() {
+        let race = |time: u128, record: u128| Race { time, record };
+
+        // The window is symmetric, so it is exact if its first hold time wins
+        // and the one before it does not.
+        let assert_window = |race: Race| {
+            let ways = race.ways_to_win();
+            let low = (race.time + 1 - ways) / 2;
+            assert!(race.wins(low), "{race:?}");
+            assert!(!race.wins(low - 1), "{race:?}");
+        };
+
+        // The discriminant overflows, so these take the binary search path.
+        assert_eq!(race(u128::MAX, 0).ways_to_win(), u128::MAX - 1);
+        assert_eq!(race(u128::MAX, u128::MAX).ways_to_win(), u128::MAX - 3);
+        assert_window(race(1 << 66, u128::MAX));
+        assert_window(race(u128::MAX / 3, u128::MAX / 7));
+
+        let time = 1u128 << 64;
+        let record = (time / 2) * (time / 2) - 1;
+        assert_eq!(race(time, record).ways_to_win(), 1);
+        assert_eq!(race(time - 1, record).ways_to_win(), 0);
+        assert_window(race(time + 1, record));
+        assert_window(race(time - 2, record / 3));
+    }
+
+    #[test_log::test]
+    fn test_too_large() {
+        let digits = "9".repeat(40);
+        let input = format!("Time: {digits}\nDistance: 1");
+
+        assert_eq!(part_1(&input), Err(RaceError::TooLarge { value: digits }));
+        assert_eq!(
+            part_1("Time: 100 100 100\nDistance: 0 0 0").map(|n| n > 0),
+            Ok(true)
+        );
+        assert_eq!(
+            part_1(&format!("Time: {0} {0}\nDistance: 0 0", u128::MAX)),
+            Err(RaceError::Overflow)
+        );
     }
 }
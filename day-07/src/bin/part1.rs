@@ -1,3 +1,4 @@
+use day_07::camel::Ruleset;
 use day_07::part1::process;
 use miette::Context;
 
@@ -14,6 +15,14 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input.txt");
+
+    if std::env::args().any(|arg| arg == "--ranked") {
+        for ranked in Ruleset::standard().ranked(file).context("rank part 1")? {
+            println!("{ranked}");
+        }
+        return Ok(());
+    }
+
     let result = process(file).context("process part 1")?;
 
     println!("{}", result);
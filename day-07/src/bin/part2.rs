@@ -1,3 +1,4 @@
+use day_07::camel::Ruleset;
 use day_07::part2::process;
 use miette::Context;
 
@@ -14,6 +15,14 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input.txt");
+
+    if std::env::args().any(|arg| arg == "--ranked") {
+        for ranked in Ruleset::jokers().ranked(file).context("rank part 2")? {
+            println!("{ranked}");
+        }
+        return Ok(());
+    }
+
     let result = process(file).context("process part 1")?;
 
     println!("{}", result);
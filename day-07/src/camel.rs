@@ -0,0 +1,538 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use nom::{
+    character::complete::{self, alphanumeric1, newline, space1},
+    multi::separated_list1,
+    sequence::separated_pair,
+    IResult,
+};
+
+use crate::error::AocError;
+
+/// The rules of a game of Camel Cards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ruleset {
+    /// Card labels from weakest to strongest, used to break ties.
+    pub card_order: Vec<char>,
+    /// A card standing in for whichever card makes the best hand type.
+    pub wildcard: Option<char>,
+    pub hand_size: usize,
+    /// Card positions compared, in order, between hands of the same type.
+    pub tie_break: Vec<usize>,
+}
+
+impl Ruleset {
+    fn new(card_order: &str, wildcard: Option<char>, hand_size: usize) -> Self {
+        Self {
+            card_order: card_order.chars().collect(),
+            wildcard,
+            hand_size,
+            tie_break: (0..hand_size).collect(),
+        }
+    }
+
+    /// The rules of part 1.
+    pub fn standard() -> Self {
+        Self::new("23456789TJQKA", None, 5)
+    }
+
+    /// The rules of part 2: `J` is a joker, and the weakest card on its own.
+    pub fn jokers() -> Self {
+        Self::new("J23456789TQKA", Some('J'), 5)
+    }
+
+    fn rank(&self, card: char, hand: &str) -> Result<usize, AocError> {
+        self.card_order
+            .iter()
+            .position(|&c| c == card)
+            .ok_or_else(|| AocError::UnknownCard {
+                card,
+                hand: hand.to_string(),
+            })
+    }
+
+    /// The best hand type `ranks` can make, and the rank the wildcards stand
+    /// in for. Wildcards always do best joining the largest group of other
+    /// cards, the strongest one if several are equally large.
+    fn classify(&self, ranks: &[usize]) -> (HandType, Option<usize>) {
+        let mut histogram = vec![0; self.card_order.len()];
+        for &rank in ranks {
+            histogram[rank] += 1;
+        }
+
+        let wild = self
+            .wildcard
+            .and_then(|card| self.card_order.iter().position(|&c| c == card))
+            .filter(|&wild| histogram[wild] > 0);
+        let Some(wild) = wild else {
+            return (HandType::from_histogram(histogram), None);
+        };
+
+        let wildcards = std::mem::take(&mut histogram[wild]);
+        let target = (0..histogram.len())
+            .filter(|&rank| rank != wild)
+            .max_by_key(|&rank| (histogram[rank], rank));
+        histogram[target.unwrap_or(wild)] += wildcards;
+
+        (HandType::from_histogram(histogram), target)
+    }
+
+    pub fn hand(&self, cards: &str) -> Result<Hand, AocError> {
+        if let Some(&position) = self.tie_break.iter().find(|&&i| i >= self.hand_size) {
+            return Err(AocError::TieBreak {
+                position,
+                hand_size: self.hand_size,
+            });
+        }
+        if cards.chars().count() != self.hand_size {
+            return Err(AocError::HandSize {
+                hand: cards.to_string(),
+                expected: self.hand_size,
+            });
+        }
+
+        let ranks = cards
+            .chars()
+            .map(|card| self.rank(card, cards))
+            .collect::<Result<Vec<usize>, AocError>>()?;
+        let (hand_type, substitution) = self.classify(&ranks);
+
+        Ok(Hand {
+            hand_type,
+            tie_break: self.tie_break.iter().map(|&i| ranks[i]).collect(),
+            cards: cards.to_string(),
+            substitution: substitution.map(|rank| self.card_order[rank]),
+        })
+    }
+
+    /// Classifies both hands and explains which one wins.
+    pub fn compare(&self, left: &str, right: &str) -> Result<Comparison, AocError> {
+        let (left, right) = (self.hand(left)?, self.hand(right)?);
+
+        let reason = if left.hand_type != right.hand_type {
+            Reason::HandType
+        } else {
+            left.tie_break
+                .iter()
+                .zip(&right.tie_break)
+                .position(|(l, r)| l != r)
+                .map_or(Reason::Equal, |i| Reason::Card(self.tie_break[i]))
+        };
+
+        Ok(Comparison {
+            ordering: left.cmp(&right),
+            left,
+            right,
+            reason,
+        })
+    }
+
+    /// Every hand in `input` with its bid, weakest hand first.
+    pub fn ranked(&self, input: &str) -> Result<Vec<Ranked>, AocError> {
+        let (rest, lines) = parse(input).map_err(|e| AocError::Parse(e.to_string()))?;
+        let rest = rest.trim_start();
+        if !rest.is_empty() {
+            let line = input[..input.len() - rest.len()].matches('\n').count() + 1;
+            let text = rest.lines().next().unwrap_or_default();
+            return Err(AocError::Parse(format!(
+                "line {line} is not a hand and a bid: {text:?}"
+            )));
+        }
+
+        let mut game = lines
+            .into_iter()
+            .map(|(cards, bid)| Ok((self.hand(cards)?, bid)))
+            .collect::<Result<Vec<(Hand, u32)>, AocError>>()?;
+        game.sort();
+
+        Ok(game
+            .into_iter()
+            .enumerate()
+            .map(|(pos, (hand, bid))| Ranked {
+                hand,
+                bid,
+                rank: pos + 1,
+            })
+            .collect())
+    }
+
+    /// Total winnings: every bid multiplied by the rank of its hand, weakest
+    /// hand first.
+    pub fn winnings(&self, input: &str) -> Result<u64, AocError> {
+        Ok(self.ranked(input)?.iter().map(Ranked::winnings).sum())
+    }
+}
+
+/// The shape of a hand: the sizes of its groups of matching cards, largest
+/// first. Comparing shapes element by element orders them from high card up
+/// to five of a kind, and extends naturally to other hand sizes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HandType(pub Vec<usize>);
+
+impl HandType {
+    fn from_histogram(mut histogram: Vec<usize>) -> Self {
+        histogram.retain(|&count| count > 0);
+        histogram.sort_unstable_by(|a, b| b.cmp(a));
+        Self(histogram)
+    }
+}
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rest_single = |rest: &[usize]| rest.iter().all(|&count| count == 1);
+
+        match self.0.as_slice() {
+            [] => write!(f, "empty hand"),
+            [1, ..] => write!(f, "high card"),
+            [2, rest @ ..] if rest_single(rest) => write!(f, "one pair"),
+            [2, 2, rest @ ..] if rest_single(rest) => write!(f, "two pair"),
+            [3, 2] => write!(f, "full house"),
+            [n, rest @ ..] if rest_single(rest) => match n {
+                3 => write!(f, "three of a kind"),
+                4 => write!(f, "four of a kind"),
+                5 => write!(f, "five of a kind"),
+                6 => write!(f, "six of a kind"),
+                n => write!(f, "{n} of a kind"),
+            },
+            counts => {
+                let counts: Vec<String> = counts.iter().map(usize::to_string).collect();
+                write!(f, "{}", counts.join("+"))
+            }
+        }
+    }
+}
+
+/// A hand classified under a `Ruleset`. Hands order by type, then by the
+/// ranks of their cards in the ruleset's tie-break order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hand {
+    pub hand_type: HandType,
+    pub tie_break: Vec<usize>,
+    pub cards: String,
+    /// The card every wildcard in the hand stands in for.
+    pub substitution: Option<char>,
+}
+
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}", self.cards, self.hand_type)?;
+        if let Some(card) = self.substitution {
+            write!(f, ", wildcards as {card}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Why one hand beats another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The hands are of different types.
+    HandType,
+    /// The hands are of the same type and first differ at this card position.
+    Card(usize),
+    /// The hands are of the same type and tie on every compared card.
+    Equal,
+}
+
+/// Two hands compared under a `Ruleset`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub left: Hand,
+    pub right: Hand,
+    pub ordering: Ordering,
+    pub reason: Reason,
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (winner, loser) = match self.ordering {
+            Ordering::Less => (&self.right, &self.left),
+            Ordering::Equal => return write!(f, "{} ties {}", self.left, self.right),
+            Ordering::Greater => (&self.left, &self.right),
+        };
+
+        write!(f, "{winner} beats {loser}: ")?;
+        match self.reason {
+            Reason::HandType => write!(f, "{} beats {}", winner.hand_type, loser.hand_type),
+            Reason::Card(position) => {
+                let card = |hand: &Hand| hand.cards.chars().nth(position).unwrap();
+                write!(
+                    f,
+                    "card {} {} beats {}",
+                    position + 1,
+                    card(winner),
+                    card(loser)
+                )
+            }
+            Reason::Equal => write!(f, "equal hands"),
+        }
+    }
+}
+
+/// A hand's place in a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ranked {
+    pub hand: Hand,
+    pub bid: u32,
+    /// 1 for the weakest hand.
+    pub rank: usize,
+}
+
+impl Ranked {
+    pub fn winnings(&self) -> u64 {
+        self.bid as u64 * self.rank as u64
+    }
+}
+
+impl fmt::Display for Ranked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>5} {:<40} {:>4} {:>8}",
+            self.rank,
+            self.hand.to_string(),
+            self.bid,
+            self.winnings()
+        )
+    }
+}
+
+fn parse_line(input: &str) -> IResult<&str, (&str, u32)> {
+    separated_pair(alphanumeric1, space1, complete::u32)(input)
+}
+
+fn parse(input: &str) -> IResult<&str, Vec<(&str, u32)>> {
+    separated_list1(newline, parse_line)(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand_type(ruleset: &Ruleset, cards: &str) -> String {
+        ruleset.hand(cards).unwrap().hand_type.to_string()
+    }
+
+    #[test_log::test]
+    fn test_parse_line() {
+        assert_eq!(("AAAAA", 123), parse_line("AAAAA 123").unwrap().1);
+    }
+
+    #[test_log::test]
+    fn test_invalid_hands() {
+        let ruleset = Ruleset::standard();
+
+        assert!(matches!(
+            ruleset.hand("AAAA"),
+            Err(AocError::HandSize { expected: 5, .. })
+        ));
+        assert!(matches!(
+            ruleset.hand("AAAAX"),
+            Err(AocError::UnknownCard { card: 'X', .. })
+        ));
+
+        let short = Ruleset {
+            hand_size: 4,
+            ..Ruleset::standard()
+        };
+        assert!(matches!(
+            short.hand("AAKK"),
+            Err(AocError::TieBreak {
+                position: 4,
+                hand_size: 4
+            })
+        ));
+        let short = Ruleset {
+            tie_break: (0..4).collect(),
+            ..short
+        };
+        assert_eq!(
+            short.hand("AAKK").unwrap().hand_type.to_string(),
+            "two pair"
+        );
+    }
+
+    #[test_log::test]
+    fn test_twos_wild() {
+        let ruleset = Ruleset {
+            wildcard: Some('2'),
+            ..Ruleset::standard()
+        };
+
+        assert_eq!(hand_type(&ruleset, "2345A"), "one pair");
+        assert_eq!(hand_type(&ruleset, "22KKQ"), "four of a kind");
+        assert_eq!(hand_type(&ruleset, "22222"), "five of a kind");
+        assert!(ruleset.hand("2AAAA").unwrap() > ruleset.hand("3AAAA").unwrap());
+        assert!(ruleset.hand("2AAAA").unwrap() < ruleset.hand("AAAAA").unwrap());
+    }
+
+    #[test_log::test]
+    fn test_six_card_hands() {
+        let ruleset = Ruleset {
+            hand_size: 6,
+            tie_break: (0..6).collect(),
+            ..Ruleset::jokers()
+        };
+
+        assert_eq!(hand_type(&ruleset, "AAAAAA"), "six of a kind");
+        assert_eq!(hand_type(&ruleset, "AAAKKJ"), "4+2");
+        assert_eq!(hand_type(&ruleset, "AAKKQQ"), "2+2+2");
+        assert_eq!(hand_type(&ruleset, "AAAKKK"), "3+3");
+        assert!(ruleset.hand("AAAKKQ").unwrap() > ruleset.hand("AAKKQQ").unwrap());
+        assert_eq!(ruleset.winnings("AAAKKQ 10\nAAKKQQ 1").unwrap(), 21);
+    }
+
+    /// The original classifier, branching on the number of distinct cards.
+    fn distinct_count_type(cards: &str, jokers: bool) -> &'static str {
+        let distinct = cards.chars().collect::<std::collections::BTreeSet<char>>();
+        let counts: Vec<usize> = distinct
+            .iter()
+            .map(|d| cards.chars().filter(|c| c == d).count())
+            .collect();
+        let num_jokers = if jokers {
+            cards.chars().filter(|c| *c == 'J').count()
+        } else {
+            0
+        };
+
+        match distinct.len() {
+            1 => "five of a kind",
+            2 if counts.contains(&4) => match num_jokers {
+                1 | 4 => "five of a kind",
+                _ => "four of a kind",
+            },
+            2 => match num_jokers {
+                2..=3 => "five of a kind",
+                _ => "full house",
+            },
+            3 if counts.contains(&3) => match num_jokers {
+                1 | 3 => "four of a kind",
+                2 => "five of a kind",
+                _ => "three of a kind",
+            },
+            3 => match num_jokers {
+                2 => "four of a kind",
+                1 => "full house",
+                _ => "two pair",
+            },
+            4 => match num_jokers {
+                3 => "five of a kind",
+                1..=2 => "three of a kind",
+                _ => "one pair",
+            },
+            _ => match num_jokers {
+                1 => "one pair",
+                _ => "high card",
+            },
+        }
+    }
+
+    #[test_log::test]
+    fn test_matches_distinct_count_classifier() {
+        let labels: Vec<char> = "23456789TJQKA".chars().collect();
+        let (standard, jokers) = (Ruleset::standard(), Ruleset::jokers());
+
+        for mut n in 0..13_usize.pow(5) {
+            let cards: String = (0..5)
+                .map(|_| {
+                    let card = labels[n % 13];
+                    n /= 13;
+                    card
+                })
+                .collect();
+
+            assert_eq!(
+                hand_type(&standard, &cards),
+                distinct_count_type(&cards, false),
+                "{cards}"
+            );
+            assert_eq!(
+                hand_type(&jokers, &cards),
+                distinct_count_type(&cards, true),
+                "{cards}"
+            );
+        }
+    }
+
+    #[test_log::test]
+    fn test_substitution() {
+        let ruleset = Ruleset::jokers();
+
+        assert_eq!(ruleset.hand("KTJJT").unwrap().substitution, Some('T'));
+        assert_eq!(ruleset.hand("KKJTT").unwrap().substitution, Some('K'));
+        assert_eq!(ruleset.hand("JJJJJ").unwrap().substitution, Some('A'));
+        assert_eq!(ruleset.hand("32T3K").unwrap().substitution, None);
+        assert_eq!(
+            ruleset.hand("QQQJA").unwrap().to_string(),
+            "QQQJA (four of a kind, wildcards as Q)"
+        );
+    }
+
+    #[test_log::test]
+    fn test_compare() {
+        let ruleset = Ruleset::jokers();
+
+        let comparison = ruleset.compare("32T3K", "KTJJT").unwrap();
+        assert_eq!(comparison.ordering, Ordering::Less);
+        assert_eq!(comparison.reason, Reason::HandType);
+        assert_eq!(
+            comparison.to_string(),
+            "KTJJT (four of a kind, wildcards as T) beats 32T3K (one pair): \
+             four of a kind beats one pair"
+        );
+
+        let comparison = ruleset.compare("QQQJA", "T55J5").unwrap();
+        assert_eq!(comparison.ordering, Ordering::Greater);
+        assert_eq!(comparison.reason, Reason::Card(0));
+        assert!(comparison.to_string().ends_with("card 1 Q beats T"));
+
+        let comparison = ruleset.compare("KKJ77", "KKJ77").unwrap();
+        assert_eq!(comparison.reason, Reason::Equal);
+        assert!(comparison.to_string().contains(" ties "));
+    }
+
+    #[test_log::test]
+    fn test_ranked() {
+        let ranked = Ruleset::jokers()
+            .ranked(include_str!("../test-input.txt"))
+            .unwrap();
+
+        let order: Vec<(&str, usize, u64)> = ranked
+            .iter()
+            .map(|ranked| (ranked.hand.cards.as_str(), ranked.rank, ranked.winnings()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("32T3K", 1, 765),
+                ("KK677", 2, 56),
+                ("T55J5", 3, 2052),
+                ("QQQJA", 4, 1932),
+                ("KTJJT", 5, 1100),
+            ]
+        );
+    }
+
+    #[test_log::test]
+    fn test_ranked_rejects_bad_line() {
+        let Err(AocError::Parse(message)) =
+            Ruleset::standard().ranked("32T3K 765\nKK677 28\nbad line\nQQQJA 483")
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(message, "line 3 is not a hand and a bid: \"bad line\"");
+
+        assert_eq!(Ruleset::standard().ranked("32T3K 765\n").unwrap().len(), 1);
+    }
+
+    #[test_log::test]
+    fn test_tie_break_order() {
+        let ruleset = Ruleset {
+            tie_break: vec![4, 3, 2, 1, 0],
+            ..Ruleset::standard()
+        };
+
+        assert!(ruleset.hand("2345A").unwrap() > ruleset.hand("AKQJ9").unwrap());
+    }
+}
@@ -6,4 +6,20 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+
+    #[error("could not parse input: {0}")]
+    #[diagnostic(code(aoc::parse_error))]
+    Parse(String),
+
+    #[error("unknown card {card:?} in hand {hand}")]
+    #[diagnostic(code(aoc::unknown_card))]
+    UnknownCard { card: char, hand: String },
+
+    #[error("hand {hand} does not have {expected} cards")]
+    #[diagnostic(code(aoc::hand_size))]
+    HandSize { hand: String, expected: usize },
+
+    #[error("tie-break position {position} is outside a hand of {hand_size} cards")]
+    #[diagnostic(code(aoc::tie_break))]
+    TieBreak { position: usize, hand_size: usize },
 }
@@ -1,3 +1,4 @@
+pub mod camel;
 pub mod error;
 
 pub mod part1;
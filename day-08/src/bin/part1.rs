@@ -1,3 +1,4 @@
+use day_08::network::Network;
 use day_08::part1::process;
 use miette::Context;
 
@@ -14,6 +15,19 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input.txt");
+
+    if std::env::args().any(|arg| arg == "--analyse") {
+        let network = Network::parse(file)?;
+        for (node, reference) in network.dangling() {
+            println!("dangling: {node} leads to undefined node {reference}");
+        }
+        let analysis = network.analyse("AAA", |node| node == "ZZZ");
+        for node in analysis.unreachable() {
+            println!("unreachable: {node}");
+        }
+        println!("{:?}", analysis.outcome);
+    }
+
     let result = process(file).context("process part 1")?;
 
     println!("{}", result);
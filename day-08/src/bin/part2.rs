@@ -1,3 +1,5 @@
+use day_08::dot::{dot, DotOptions};
+use day_08::network::Network;
 use day_08::part2::process;
 use miette::Context;
 
@@ -14,6 +16,17 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input.txt");
+
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|arg| arg == "--dot") {
+        let options = DotOptions {
+            states: args.iter().any(|arg| arg == "--states"),
+            cycles: args.iter().any(|arg| arg == "--cycles"),
+        };
+        print!("{}", dot(&Network::parse(file)?, options));
+        return Ok(());
+    }
+
     let result = process(file).context("process part 1")?;
 
     println!("{}", result);
@@ -0,0 +1,271 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::network::Network;
+
+/// Colours given to the detected cycles, in turn.
+const PALETTE: [&str; 6] = [
+    "crimson",
+    "darkorange",
+    "forestgreen",
+    "royalblue",
+    "purple",
+    "goldenrod",
+];
+
+type State = (u32, usize);
+
+/// How to draw the network.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DotOptions {
+    /// Draw the (node, instruction index) states reachable from the `A`
+    /// nodes instead of the nodes, with runs of states that can only be
+    /// walked one way collapsed into a single edge.
+    pub states: bool,
+    /// Colour the cycle each walk from an `A` node ends up in.
+    pub cycles: bool,
+}
+
+/// The network as a Graphviz digraph. `L` edges are solid and `R` edges
+/// dashed; `A` nodes are green, `Z` nodes red and undefined nodes dotted.
+pub fn dot(network: &Network, options: DotOptions) -> String {
+    let starts: Vec<u32> = network
+        .nodes()
+        .filter(|&id| network.name(id).ends_with('A'))
+        .collect();
+
+    let mut colours: HashMap<State, &str> = HashMap::new();
+    if options.cycles {
+        for (cycle, colour) in cycles(network, &starts).iter().zip(PALETTE.iter().cycle()) {
+            for &state in cycle {
+                colours.entry(state).or_insert(colour);
+            }
+        }
+    }
+
+    let mut out = String::from("digraph network {\n");
+    if options.states {
+        write_states(&mut out, network, &starts, &colours);
+    } else {
+        write_nodes(&mut out, network, &colours);
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Every distinct cycle the walks from `starts` end up in, as the states
+/// along it.
+fn cycles(network: &Network, starts: &[u32]) -> Vec<Vec<State>> {
+    let len = network.instructions.len();
+    let mut cycles: Vec<Vec<State>> = Vec::new();
+
+    for &start in starts {
+        let Ok((cycle_start, period)) = network.cycle(start) else {
+            continue;
+        };
+
+        let mut state = (start, 0);
+        for _ in 0..cycle_start {
+            state = (network.step(state.0, state.1), (state.1 + 1) % len);
+        }
+        let mut cycle = Vec::with_capacity(period as usize);
+        for _ in 0..period {
+            cycle.push(state);
+            state = (network.step(state.0, state.1), (state.1 + 1) % len);
+        }
+
+        if !cycles.iter().any(|seen| seen.contains(&cycle[0])) {
+            cycles.push(cycle);
+        }
+    }
+
+    cycles
+}
+
+fn node_attributes(network: &Network, id: u32) -> Vec<String> {
+    let name = network.name(id);
+    let mut attributes = Vec::new();
+    if !network.is_defined(id) {
+        attributes.push("style=dotted".to_string());
+    } else if name.ends_with('A') {
+        attributes.push("style=filled, fillcolor=palegreen".to_string());
+    } else if name.ends_with('Z') {
+        attributes.push("style=filled, fillcolor=lightcoral".to_string());
+    }
+    attributes
+}
+
+fn edge_attributes(direction: char) -> Vec<String> {
+    let style = if direction == 'L' { "solid" } else { "dashed" };
+    vec![format!("style={style}")]
+}
+
+fn coloured(mut attributes: Vec<String>, colour: Option<&&str>) -> String {
+    if let Some(colour) = colour {
+        attributes.push(format!("color={colour}, penwidth=2"));
+    }
+    attributes.join(", ")
+}
+
+fn write_nodes(out: &mut String, network: &Network, colours: &HashMap<State, &str>) {
+    // A node or edge is on a cycle if it is at any instruction index.
+    let mut node_colours: HashMap<u32, &str> = HashMap::new();
+    let mut edge_colours: HashMap<(u32, char), &str> = HashMap::new();
+    for (&(id, index), &colour) in colours {
+        node_colours.entry(id).or_insert(colour);
+        edge_colours
+            .entry((id, network.instructions[index]))
+            .or_insert(colour);
+    }
+
+    for id in 0..network.node_count() as u32 {
+        let attributes = node_attributes(network, id);
+        let _ = writeln!(
+            out,
+            "    \"{}\" [{}];",
+            network.name(id),
+            coloured(attributes, node_colours.get(&id))
+        );
+    }
+
+    for id in network.nodes() {
+        for (direction, next) in [('L', network.left(id)), ('R', network.right(id))] {
+            let mut attributes = edge_attributes(direction);
+            attributes.insert(0, format!("label={direction}"));
+            let _ = writeln!(
+                out,
+                "    \"{}\" -> \"{}\" [{}];",
+                network.name(id),
+                network.name(next),
+                coloured(attributes, edge_colours.get(&(id, direction)))
+            );
+        }
+    }
+}
+
+fn write_states(
+    out: &mut String,
+    network: &Network,
+    starts: &[u32],
+    colours: &HashMap<State, &str>,
+) {
+    let len = network.instructions.len();
+    let successor = |(id, index): State| (network.step(id, index), (index + 1) % len);
+
+    // Every reachable state, in the order the walks first reach them, and
+    // how many reachable states lead to each one.
+    let mut order: Vec<State> = Vec::new();
+    let mut incoming: HashMap<State, usize> = HashMap::new();
+    let mut seen: HashSet<State> = HashSet::new();
+    for &start in starts {
+        let mut state = (start, 0);
+        while seen.insert(state) {
+            order.push(state);
+            if !network.is_defined(state.0) {
+                break;
+            }
+            state = successor(state);
+            *incoming.entry(state).or_default() += 1;
+        }
+    }
+
+    let kept = |state: State| {
+        let (id, index) = state;
+        (starts.contains(&id) && index == 0)
+            || network.name(id).ends_with('Z')
+            || !network.is_defined(id)
+            || incoming.get(&state).copied().unwrap_or_default() > 1
+    };
+    let label = |(id, index): State| format!("{}@{index}", network.name(id));
+
+    for &state in order.iter().filter(|&&state| kept(state)) {
+        let _ = writeln!(
+            out,
+            "    \"{}\" [{}];",
+            label(state),
+            coloured(node_attributes(network, state.0), colours.get(&state))
+        );
+    }
+
+    for &state in order.iter().filter(|&&state| kept(state)) {
+        if !network.is_defined(state.0) {
+            continue;
+        }
+
+        let mut next = successor(state);
+        let mut steps = 1;
+        while !kept(next) {
+            next = successor(next);
+            steps += 1;
+        }
+
+        let mut attributes = edge_attributes(network.instructions[state.1]);
+        attributes.insert(0, format!("label={steps}"));
+        let _ = writeln!(
+            out,
+            "    \"{}\" -> \"{}\" [{}];",
+            label(state),
+            label(next),
+            coloured(attributes, colours.get(&state))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETWORK: &str = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+XXX = (XXX, YYY)";
+
+    #[test_log::test]
+    fn test_dot_nodes() {
+        let network = Network::parse(NETWORK).unwrap();
+        let dot = dot(&network, DotOptions::default());
+
+        assert!(dot.starts_with("digraph network {\n"));
+        assert!(dot.contains("    \"11A\" [style=filled, fillcolor=palegreen];\n"));
+        assert!(dot.contains("    \"11Z\" [style=filled, fillcolor=lightcoral];\n"));
+        assert!(dot.contains("    \"YYY\" [style=dotted];\n"));
+        assert!(dot.contains("    \"11A\" -> \"11B\" [label=L, style=solid];\n"));
+        assert!(dot.contains("    \"11B\" -> \"11Z\" [label=R, style=dashed];\n"));
+    }
+
+    #[test_log::test]
+    fn test_dot_cycles() {
+        let network = Network::parse(NETWORK).unwrap();
+        let dot = dot(
+            &network,
+            DotOptions {
+                states: false,
+                cycles: true,
+            },
+        );
+
+        assert!(dot.contains("    \"11B\" [color=crimson, penwidth=2];\n"));
+        assert!(dot.contains(
+            "    \"11Z\" -> \"11B\" [label=L, style=solid, color=crimson, penwidth=2];\n"
+        ));
+        assert!(dot.contains("    \"11A\" -> \"11B\" [label=L, style=solid];\n"));
+    }
+
+    #[test_log::test]
+    fn test_dot_states() {
+        let network = Network::parse(include_str!("../test-input.txt")).unwrap();
+        let dot = dot(
+            &network,
+            DotOptions {
+                states: true,
+                cycles: true,
+            },
+        );
+
+        assert!(dot.contains("    \"AAA@0\" -> \"ZZZ@0\" [label=6, style=solid];\n"));
+        assert!(dot.contains("    \"ZZZ@1\" -> \"ZZZ@2\" [label=1, style=solid, color=crimson"));
+        assert!(!dot.contains("BBB"));
+    }
+}
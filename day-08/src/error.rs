@@ -6,4 +6,28 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+
+    #[error("could not parse the network: {0}")]
+    #[diagnostic(code(aoc::parse_error))]
+    Parse(String),
+
+    #[error("the walk reaches undefined node {node} after {steps} steps")]
+    #[diagnostic(code(aoc::undefined_node))]
+    UndefinedNode { node: String, steps: u64 },
+
+    #[error("{target} can never be reached from {start}")]
+    #[diagnostic(
+        code(aoc::unreachable),
+        help("the walk repeats every {period} steps from step {cycle_start} on without visiting {target}")
+    )]
+    Unreachable {
+        start: String,
+        target: String,
+        cycle_start: u64,
+        period: u64,
+    },
+
+    #[error("the ghosts never all stand on nodes ending in Z at the same time")]
+    #[diagnostic(code(aoc::never_aligned))]
+    NeverAligned,
 }
@@ -0,0 +1,190 @@
+use crate::network::Network;
+
+/// Every step at which a ghost stands on a target node.
+///
+/// The ghost's (node, instruction index) state first repeats after
+/// `cycle_start + period` steps, so from `cycle_start` on its walk is periodic:
+/// it is on a target at each offset in `tail` once, and at each offset in
+/// `cycle` plus any multiple of `period`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hits {
+    pub tail: Vec<u64>,
+    pub cycle: Vec<u64>,
+    pub cycle_start: u64,
+    pub period: u64,
+}
+
+impl Hits {
+    /// Walks the network from `start` through one full cycle, where
+    /// `targets[id]` tells whether node `id` is a target. If the walk stands
+    /// on an undefined node first, returns that node and the step it got
+    /// there.
+    pub fn find(network: &Network, start: u32, targets: &[bool]) -> Result<Self, (u32, u64)> {
+        let (cycle_start, period) = network.cycle(start)?;
+        let mut tail = Vec::new();
+        let mut cycle = Vec::new();
+
+        let mut node = start;
+        for step in 0..cycle_start + period {
+            if targets[node as usize] {
+                if step < cycle_start {
+                    tail.push(step);
+                } else {
+                    cycle.push(step);
+                }
+            }
+            node = network.step(node, step as usize % network.instructions.len());
+        }
+
+        Ok(Self {
+            tail,
+            cycle,
+            cycle_start,
+            period,
+        })
+    }
+
+    pub fn contains(&self, step: u64) -> bool {
+        if step < self.cycle_start {
+            self.tail.contains(&step)
+        } else {
+            let offset = (step - self.cycle_start) % self.period + self.cycle_start;
+            self.cycle.contains(&offset)
+        }
+    }
+}
+
+/// The first step at which every ghost stands on a target at once, if there
+/// is one.
+pub fn first_common(ghosts: &[Hits]) -> Option<u64> {
+    let settled = ghosts.iter().map(|hits| hits.cycle_start).max()?;
+
+    // Before every ghost is in its cycle, the ghost that settles last can
+    // only be on a target at one of its tail offsets.
+    let slowest = ghosts.iter().max_by_key(|hits| hits.cycle_start)?;
+    if let Some(step) = slowest
+        .tail
+        .iter()
+        .copied()
+        .find(|&step| ghosts.iter().all(|hits| hits.contains(step)))
+    {
+        return Some(step);
+    }
+
+    // From then on each ghost is on a target exactly when the step is
+    // congruent to one of its cycle offsets modulo its period.
+    let mut congruences = vec![(0, 1)];
+    for hits in ghosts {
+        let mut combined: Vec<(u128, u128)> = congruences
+            .iter()
+            .flat_map(|&congruence| {
+                hits.cycle.iter().filter_map(move |&offset| {
+                    let residue = (offset % hits.period) as u128;
+                    crt(congruence, (residue, hits.period as u128))
+                })
+            })
+            .collect();
+        combined.sort_unstable();
+        combined.dedup();
+        congruences = combined;
+    }
+
+    congruences
+        .into_iter()
+        .map(|(residue, modulus)| {
+            let settled = settled as u128;
+            if residue >= settled {
+                residue
+            } else {
+                residue + (settled - residue).div_ceil(modulus) * modulus
+            }
+        })
+        .min()
+        .and_then(|step| u64::try_from(step).ok())
+}
+
+/// Combines `x ≡ a (mod m)` and `x ≡ b (mod n)` into a single congruence
+/// modulo `lcm(m, n)`, or `None` if they contradict each other.
+fn crt((a, m): (u128, u128), (b, n): (u128, u128)) -> Option<(u128, u128)> {
+    let (g, p, _) = extended_gcd(m as i128, n as i128);
+    let diff = b as i128 - a as i128;
+    if diff % g != 0 {
+        return None;
+    }
+
+    let n_g = n as i128 / g;
+    let k = (diff / g % n_g * p % n_g).rem_euclid(n_g);
+    let lcm = m / g as u128 * n;
+    Some(((a + m * k as u128) % lcm, lcm))
+}
+
+/// `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - a / b * y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hits(tail: &[u64], cycle: &[u64], cycle_start: u64, period: u64) -> Hits {
+        Hits {
+            tail: tail.to_vec(),
+            cycle: cycle.to_vec(),
+            cycle_start,
+            period,
+        }
+    }
+
+    #[test_log::test]
+    fn test_find() {
+        let network = Network::parse(
+            "L
+
+AAA = (BBB, BBB)
+BBB = (CCC, CCC)
+CCC = (DDD, DDD)
+DDD = (BBB, BBB)
+EEE = (FFF, FFF)",
+        )
+        .unwrap();
+        let targets = network.targets(|name| name == "AAA" || name == "CCC");
+
+        let found = Hits::find(&network, 0, &targets).unwrap();
+        assert_eq!(found, hits(&[0], &[2], 1, 3));
+        assert!(found.contains(5));
+        assert!(!found.contains(6));
+
+        let eee = network.id("EEE").unwrap();
+        let fff = network.id("FFF").unwrap();
+        assert_eq!(Hits::find(&network, eee, &targets), Err((fff, 1)));
+    }
+
+    #[test_log::test]
+    fn test_crt() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(crt((0, 4), (1, 6)), None);
+    }
+
+    #[test_log::test]
+    fn test_first_common() {
+        assert_eq!(
+            first_common(&[hits(&[], &[2], 1, 2), hits(&[], &[1], 1, 3)]),
+            Some(4)
+        );
+        assert_eq!(
+            first_common(&[hits(&[3], &[6], 5, 4), hits(&[], &[1], 0, 2)]),
+            Some(3)
+        );
+        assert_eq!(
+            first_common(&[hits(&[], &[0], 0, 2), hits(&[], &[1], 1, 2)]),
+            None
+        );
+    }
+}
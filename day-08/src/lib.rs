@@ -1,4 +1,7 @@
+pub mod dot;
 pub mod error;
+pub mod ghost;
+pub mod network;
 
 pub mod part1;
 pub mod part2;
@@ -0,0 +1,439 @@
+use std::collections::HashMap;
+
+use nom::{
+    branch::alt,
+    character::complete::{self, alphanumeric1, line_ending, multispace0, newline, one_of},
+    combinator::eof,
+    multi::many1,
+    sequence::{delimited, preceded, separated_pair, terminated},
+    IResult,
+};
+
+use crate::error::AocError;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Entry<'a> {
+    pub left: &'a str,
+    pub right: &'a str,
+}
+
+/// The left/right instructions and the nodes of the desert network.
+///
+/// Node labels are interned to dense ids at parse time, so walking the
+/// network is a pair of array lookups per step. Labels that are referenced
+/// but never defined get ids too; they lead back to themselves.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Network {
+    pub instructions: Vec<char>,
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+    left: Vec<u32>,
+    right: Vec<u32>,
+    defined: Vec<bool>,
+}
+
+/// How a walk through the network ends.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    /// The walk stands on a target after `steps` steps.
+    Reached { steps: u64 },
+    /// The walk repeats every `period` steps from `cycle_start` on without
+    /// ever standing on a target.
+    Loops { cycle_start: u64, period: u64 },
+    /// The walk stands on a node without an entry after `steps` steps.
+    Undefined { node: String, steps: u64 },
+}
+
+/// The result of exploring the (node, instruction index) states reachable
+/// from a start node.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Analysis<'a> {
+    pub outcome: Outcome,
+    network: &'a Network,
+    visited: Vec<bool>,
+}
+
+impl Network {
+    pub fn parse(input: &str) -> Result<Self, AocError> {
+        let (rest, network) = parse(input).map_err(|e| AocError::Parse(e.to_string()))?;
+        if !rest.trim().is_empty() {
+            let consumed = &input[..input.len() - rest.len()];
+            let line = consumed.matches('\n').count() + 1;
+            let text = rest.lines().next().unwrap_or_default();
+            return Err(AocError::Parse(format!(
+                "line {line} is not a node entry: {text:?}"
+            )));
+        }
+        Ok(network)
+    }
+
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+
+        let id = self.names.len() as u32;
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.left.push(id);
+        self.right.push(id);
+        self.defined.push(false);
+        id
+    }
+
+    fn define(&mut self, node: &str, entry: Entry) {
+        let id = self.intern(node) as usize;
+        self.left[id] = self.intern(entry.left);
+        self.right[id] = self.intern(entry.right);
+        self.defined[id] = true;
+    }
+
+    /// The number of node ids, including undefined nodes.
+    pub fn node_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn is_defined(&self, id: u32) -> bool {
+        self.defined[id as usize]
+    }
+
+    /// The ids of every defined node, in the order they first appear.
+    pub fn nodes(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.node_count() as u32).filter(|&id| self.is_defined(id))
+    }
+
+    pub fn left(&self, id: u32) -> u32 {
+        self.left[id as usize]
+    }
+
+    pub fn right(&self, id: u32) -> u32 {
+        self.right[id as usize]
+    }
+
+    /// The node instruction `index` leads to from `id`.
+    pub fn step(&self, id: u32, index: usize) -> u32 {
+        if self.instructions[index] == 'L' {
+            self.left(id)
+        } else {
+            self.right(id)
+        }
+    }
+
+    /// Whether each node id is a target, according to its label.
+    pub fn targets<T>(&self, is_target: T) -> Vec<bool>
+    where
+        T: Fn(&str) -> bool,
+    {
+        self.names.iter().map(|name| is_target(name)).collect()
+    }
+
+    /// Every `(node, reference)` pair where `node` leads to a node that is
+    /// not defined.
+    pub fn dangling(&self) -> Vec<(&str, &str)> {
+        self.nodes()
+            .flat_map(|id| [(id, self.left(id)), (id, self.right(id))])
+            .filter(|&(_, reference)| !self.is_defined(reference))
+            .map(|(id, reference)| (self.name(id), self.name(reference)))
+            .collect()
+    }
+
+    /// Where the walk from `start` starts repeating itself, and how often it
+    /// repeats, as `(cycle_start, period)`. If the walk stands on an
+    /// undefined node first, returns that node and the step it got there.
+    ///
+    /// Uses Brent's algorithm on (node, instruction index) states, so it
+    /// needs no memory beyond a few states however long the walk is.
+    pub fn cycle(&self, start: u32) -> Result<(u64, u64), (u32, u64)> {
+        let len = self.instructions.len();
+        let advance = |(id, index): (u32, usize)| (self.step(id, index), (index + 1) % len);
+
+        if !self.is_defined(start) {
+            return Err((start, 0));
+        }
+
+        let mut power = 1;
+        let mut period = 1;
+        let mut tortoise = (start, 0);
+        let mut hare = advance(tortoise);
+        let mut steps = 1;
+        while tortoise != hare {
+            if !self.is_defined(hare.0) {
+                return Err((hare.0, steps));
+            }
+            if power == period {
+                tortoise = hare;
+                power *= 2;
+                period = 0;
+            }
+            hare = advance(hare);
+            steps += 1;
+            period += 1;
+        }
+
+        let mut tortoise = (start, 0);
+        let mut hare = (start, 0);
+        for _ in 0..period {
+            hare = advance(hare);
+        }
+        let mut cycle_start = 0;
+        while tortoise != hare {
+            tortoise = advance(tortoise);
+            hare = advance(hare);
+            cycle_start += 1;
+        }
+
+        Ok((cycle_start, period))
+    }
+
+    /// Walks from `start` until it stands on a target, on an undefined node,
+    /// or in a state it has been in before.
+    ///
+    /// The walk is deterministic, so a repeated (node, instruction index)
+    /// state proves that every later step repeats what has already been seen,
+    /// and that a target not yet reached never will be.
+    pub fn analyse<T>(&self, start: &str, is_target: T) -> Analysis<'_>
+    where
+        T: Fn(&str) -> bool,
+    {
+        let mut visited = vec![false; self.node_count()];
+        let Some(start) = self.id(start) else {
+            return Analysis {
+                outcome: Outcome::Undefined {
+                    node: start.to_string(),
+                    steps: 0,
+                },
+                network: self,
+                visited,
+            };
+        };
+
+        let targets = self.targets(is_target);
+        let cycle = self.cycle(start);
+        let end = match cycle {
+            Ok((cycle_start, period)) => cycle_start + period,
+            Err((_, steps)) => steps,
+        };
+
+        let mut node = start;
+        for step in 0..=end {
+            if targets[node as usize] {
+                visited[node as usize] = true;
+                return Analysis {
+                    outcome: Outcome::Reached { steps: step },
+                    network: self,
+                    visited,
+                };
+            }
+            if step == end {
+                break;
+            }
+            visited[node as usize] = true;
+            node = self.step(node, step as usize % self.instructions.len());
+        }
+
+        let outcome = match cycle {
+            Ok((cycle_start, period)) => Outcome::Loops {
+                cycle_start,
+                period,
+            },
+            Err((node, steps)) => Outcome::Undefined {
+                node: self.name(node).to_string(),
+                steps,
+            },
+        };
+
+        Analysis {
+            outcome,
+            network: self,
+            visited,
+        }
+    }
+}
+
+impl<'a> Analysis<'a> {
+    /// The nodes the walk visited before it ended.
+    pub fn visited(&self) -> Vec<&'a str> {
+        self.network
+            .nodes()
+            .filter(|&id| self.visited[id as usize])
+            .map(|id| self.network.name(id))
+            .collect()
+    }
+
+    /// The defined nodes the walk never visited.
+    pub fn unreachable(&self) -> Vec<&'a str> {
+        self.network
+            .nodes()
+            .filter(|&id| !self.visited[id as usize])
+            .map(|id| self.network.name(id))
+            .collect()
+    }
+}
+
+fn parse_instructions(input: &str) -> IResult<&str, Vec<char>> {
+    let (input, instructions) = terminated(many1(one_of("LR")), newline)(input)?;
+    Ok((input, instructions))
+}
+
+fn parse_entry(input: &str) -> IResult<&str, (&str, Entry<'_>)> {
+    let (input, node) = alphanumeric1(input)?;
+    let (input, _) = terminated(preceded(multispace0, complete::char('=')), multispace0)(input)?;
+    let (input, (left, right)) = delimited(
+        complete::char('('),
+        separated_pair(
+            alphanumeric1,
+            preceded(multispace0, complete::char(',')),
+            preceded(multispace0, alphanumeric1),
+        ),
+        complete::char(')'),
+    )(input)?;
+    Ok((input, (node, Entry { left, right })))
+}
+
+fn parse(input: &str) -> IResult<&str, Network> {
+    let (input, instructions) = parse_instructions(input)?;
+    let (input, _) = newline(input)?;
+    let (input, entries) = many1(terminated(parse_entry, alt((line_ending, eof))))(input)?;
+
+    let mut network = Network {
+        instructions,
+        ..Network::default()
+    };
+    for (node, entry) in entries {
+        network.define(node, entry);
+    }
+
+    Ok((input, network))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_parse_instructions() {
+        let input = "LRLRLR\n";
+        assert_eq!(
+            parse_instructions(input),
+            Ok(("", vec!['L', 'R', 'L', 'R', 'L', 'R']))
+        );
+    }
+
+    #[test_log::test]
+    fn test_parse_entry() {
+        let input = "AAA = (BBB, CCC)";
+
+        assert_eq!(
+            parse_entry(input),
+            Ok((
+                "",
+                (
+                    "AAA",
+                    Entry {
+                        left: "BBB",
+                        right: "CCC",
+                    }
+                )
+            ))
+        );
+    }
+
+    #[test_log::test]
+    fn test_interning() {
+        let network = Network::parse(include_str!("../test-input.txt")).unwrap();
+
+        let [aaa, bbb, zzz] = ["AAA", "BBB", "ZZZ"].map(|name| network.id(name).unwrap());
+        assert_eq!((aaa, bbb, zzz), (0, 1, 2));
+        assert_eq!(network.node_count(), 3);
+        assert_eq!((network.left(bbb), network.right(bbb)), (aaa, zzz));
+        assert_eq!(network.step(aaa, 2), bbb);
+        assert_eq!(network.name(zzz), "ZZZ");
+        assert_eq!(network.id("CCC"), None);
+    }
+
+    #[test_log::test]
+    fn test_cycle() {
+        let network = Network::parse(include_str!("../test-input.txt")).unwrap();
+
+        assert_eq!(network.cycle(0), Ok((6, 3)));
+        assert_eq!(network.cycle(2), Ok((0, 3)));
+    }
+
+    #[test_log::test]
+    fn test_analyse() {
+        let network = Network::parse(include_str!("../test-input.txt")).unwrap();
+
+        let analysis = network.analyse("AAA", |node| node == "ZZZ");
+        assert_eq!(analysis.outcome, Outcome::Reached { steps: 6 });
+        assert!(analysis.unreachable().is_empty());
+
+        let analysis = network.analyse("AAA", |node| node == "CCC");
+        assert_eq!(
+            analysis.outcome,
+            Outcome::Loops {
+                cycle_start: 6,
+                period: 3
+            }
+        );
+    }
+
+    #[test_log::test]
+    fn test_parse_rejects_bad_line() {
+        let Err(AocError::Parse(message)) = Network::parse(
+            "LR
+
+AAA = (BBB, ZZZ)
+BBB = (ZZZ, ZZZ)
+not an entry
+ZZZ = (ZZZ, ZZZ)
+",
+        ) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(message, "line 5 is not a node entry: \"not an entry\"");
+
+        assert!(Network::parse("LR\n\nAAA = (AAA, AAA)\n\n").is_ok());
+    }
+
+    #[test_log::test]
+    fn test_analyse_broken_network() {
+        let network = Network::parse(
+            "LR
+
+AAA = (BBB, AAA)
+BBB = (CCC, DDD)
+ZZZ = (ZZZ, ZZZ)
+",
+        )
+        .unwrap();
+
+        assert_eq!(network.dangling(), vec![("BBB", "CCC"), ("BBB", "DDD")]);
+
+        let analysis = network.analyse("AAA", |node| node == "ZZZ");
+        assert_eq!(
+            analysis.outcome,
+            Outcome::Undefined {
+                node: "DDD".to_string(),
+                steps: 2
+            }
+        );
+        assert_eq!(analysis.visited(), vec!["AAA", "BBB"]);
+        assert_eq!(analysis.unreachable(), vec!["ZZZ"]);
+
+        assert_eq!(
+            network.analyse("XXX", |_| false).outcome,
+            Outcome::Undefined {
+                node: "XXX".to_string(),
+                steps: 0
+            }
+        );
+    }
+}
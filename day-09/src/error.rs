@@ -1,4 +1,4 @@
-use miette::Diagnostic;
+use miette::{Diagnostic, NamedSource, SourceSpan};
 use thiserror::Error;
 
 #[derive(Error, Diagnostic, Debug)]
@@ -6,4 +6,45 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+
+    #[error("the value at position {position} does not fit in an i128")]
+    #[diagnostic(code(aoc::overflow))]
+    Overflow { position: i128 },
+
+    #[error("the sum of the extrapolated values does not fit in an i128")]
+    #[diagnostic(code(aoc::sum_overflow))]
+    SumOverflow,
+
+    #[error("line {line} is not a list of integers")]
+    #[diagnostic(code(aoc::invalid_value))]
+    InvalidValue {
+        line: usize,
+        #[source_code]
+        src: NamedSource,
+        #[label("expected whitespace-separated i64 values")]
+        span: SourceSpan,
+    },
+
+    #[error("the differences of line {line} do not fit in an i128")]
+    #[diagnostic(code(aoc::too_long))]
+    TooLong {
+        line: usize,
+        #[source_code]
+        src: NamedSource,
+        #[label("this history")]
+        span: SourceSpan,
+    },
+
+    #[error("the differences of line {line} never reach a row of zeros")]
+    #[diagnostic(
+        code(aoc::not_polynomial),
+        help("only histories whose differences end in zeros can be extrapolated")
+    )]
+    NotPolynomial {
+        line: usize,
+        #[source_code]
+        src: NamedSource,
+        #[label("this history")]
+        span: SourceSpan,
+    },
 }
@@ -2,3 +2,4 @@ pub mod error;
 
 pub mod part1;
 pub mod part2;
+pub mod sequence;
@@ -0,0 +1,252 @@
+use miette::{NamedSource, SourceSpan};
+
+use crate::error::AocError;
+
+/// An OASIS history, viewed as the values at `0, 1, 2, ...` of the
+/// polynomial through them.
+///
+/// The finite-difference table is built once, and only its leading diagonal
+/// is kept: the first value of every row. These are the coefficients of the
+/// polynomial in Newton's forward-difference form,
+/// `f(x) = sum(C(x, j) * heads[j])`, which can be evaluated at any position
+/// without extending the table step by step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    heads: Vec<i128>,
+    len: usize,
+}
+
+impl Sequence {
+    /// Builds the difference table of `values`, or `None` if the differences
+    /// do not fit in an `i128`.
+    pub fn new(values: &[i64]) -> Option<Self> {
+        let mut row: Vec<i128> = values.iter().map(|&value| value as i128).collect();
+        let mut heads = Vec::with_capacity(row.len());
+
+        while let Some(&head) = row.first() {
+            heads.push(head);
+            row = row
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]))
+                .collect::<Option<Vec<i128>>>()?;
+        }
+
+        while heads.last() == Some(&0) {
+            heads.pop();
+        }
+
+        Some(Self {
+            heads,
+            len: values.len(),
+        })
+    }
+
+    /// Whether the differences reduce to a row of zeros before running out,
+    /// which is the only way to tell the values come from a polynomial of
+    /// lower degree than their count, rather than just being fitted by one.
+    ///
+    /// A single value has no differences to check, and is taken to be
+    /// constant.
+    pub fn is_polynomial(&self) -> bool {
+        self.len <= 1 || self.heads.len() < self.len
+    }
+
+    /// The degree of the polynomial through the values, taking the zero
+    /// polynomial to be of degree 0.
+    pub fn degree(&self) -> usize {
+        self.heads.len().saturating_sub(1)
+    }
+
+    /// The value at `position`, where the history itself covers positions
+    /// `0..len`.
+    pub fn at(&self, position: i128) -> Result<i128, AocError> {
+        let mut value: i128 = 0;
+        // C(position, j), built up from C(position, j - 1).
+        let mut binomial: i128 = 1;
+
+        for (j, head) in self.heads.iter().enumerate() {
+            if j > 0 {
+                let j = j as i128;
+                binomial = binomial
+                    .checked_mul(position - j + 1)
+                    .ok_or(AocError::Overflow { position })?
+                    / j;
+            }
+            value = binomial
+                .checked_mul(*head)
+                .and_then(|term| value.checked_add(term))
+                .ok_or(AocError::Overflow { position })?;
+        }
+
+        Ok(value)
+    }
+
+    /// The value `steps` positions after the last one.
+    pub fn forward(&self, steps: u64) -> Result<i128, AocError> {
+        self.at(self.len as i128 - 1 + steps as i128)
+    }
+
+    /// The value `steps` positions before the first one.
+    pub fn backward(&self, steps: u64) -> Result<i128, AocError> {
+        self.at(-(steps as i128))
+    }
+}
+
+/// The sum of `extrapolate` over the usable histories, and the problems that
+/// made the other lines unusable.
+#[derive(Debug)]
+pub struct Total {
+    pub sum: i128,
+    pub skipped: Vec<AocError>,
+}
+
+/// Every history in `input`, or the problem with its line.
+pub fn parse(input: &str) -> Vec<Result<Sequence, AocError>> {
+    let src = || NamedSource::new("input", input.to_string());
+    let mut offset = 0;
+
+    input
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(i, raw)| {
+            let text = raw.trim_end();
+            let span = SourceSpan::from((offset, text.len()));
+            let line = i + 1;
+            offset += raw.len();
+
+            let values = text
+                .split_whitespace()
+                .map(|num| num.parse())
+                .collect::<Result<Vec<i64>, _>>()
+                .map_err(|_| AocError::InvalidValue {
+                    line,
+                    src: src(),
+                    span,
+                })?;
+            let sequence = Sequence::new(&values).ok_or_else(|| AocError::TooLong {
+                line,
+                src: src(),
+                span,
+            })?;
+
+            if sequence.is_polynomial() {
+                Ok(sequence)
+            } else {
+                Err(AocError::NotPolynomial {
+                    line,
+                    src: src(),
+                    span,
+                })
+            }
+        })
+        .collect()
+}
+
+pub fn total<F>(input: &str, extrapolate: F) -> Result<Total, AocError>
+where
+    F: Fn(&Sequence) -> Result<i128, AocError>,
+{
+    let mut total = Total {
+        sum: 0,
+        skipped: Vec::new(),
+    };
+
+    for sequence in parse(input) {
+        match sequence {
+            Ok(sequence) => {
+                total.sum = total
+                    .sum
+                    .checked_add(extrapolate(&sequence)?)
+                    .ok_or(AocError::SumOverflow)?;
+            }
+            Err(problem) => total.skipped.push(problem),
+        }
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(values: &[i64]) -> Sequence {
+        Sequence::new(values).unwrap()
+    }
+
+    #[test_log::test]
+    fn test_degree() {
+        assert_eq!(sequence(&[0, 3, 6, 9, 12, 15]).degree(), 1);
+        assert_eq!(sequence(&[1, 3, 6, 10, 15, 21]).degree(), 2);
+        assert_eq!(sequence(&[10, 13, 16, 21, 30, 45]).degree(), 3);
+        assert_eq!(sequence(&[7, 7, 7]).degree(), 0);
+        assert_eq!(sequence(&[0, 0]).degree(), 0);
+    }
+
+    #[test_log::test]
+    fn test_extrapolate() -> miette::Result<()> {
+        let triangular = sequence(&[1, 3, 6, 10, 15, 21]);
+
+        assert_eq!(triangular.forward(1)?, 28);
+        assert_eq!(triangular.forward(2)?, 36);
+        assert_eq!(triangular.backward(1)?, 0);
+        assert_eq!(triangular.backward(3)?, 1);
+        assert_eq!(triangular.at(2)?, 6);
+
+        let k = 1_000_000_000;
+        let n = 6 + k as i128;
+        assert_eq!(triangular.forward(k)?, n * (n + 1) / 2);
+
+        assert_eq!(sequence(&[10, 13, 16, 21, 30, 45]).backward(1)?, 5);
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_not_polynomial() {
+        assert!(sequence(&[1, 2, 4, 7]).is_polynomial());
+        assert!(sequence(&[0]).is_polynomial());
+        assert!(sequence(&[]).is_polynomial());
+        assert!(!sequence(&[1, 2, 4, 8]).is_polynomial());
+        assert!(sequence(&[5]).is_polynomial());
+        assert!(!sequence(&[5, 6]).is_polynomial());
+    }
+
+    #[test_log::test]
+    fn test_single_value() -> miette::Result<()> {
+        let single = sequence(&[5]);
+
+        assert_eq!(single.degree(), 0);
+        assert_eq!(single.forward(1)?, 5);
+        assert_eq!(single.backward(1)?, 5);
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_total_skips_bad_lines() -> miette::Result<()> {
+        let input = "0 3 6 9 12 15\n1 2 4 8\n1 3 6 10 15 21\n1 x 3\n";
+        let total = total(input, |sequence| sequence.forward(1))?;
+
+        assert_eq!(total.sum, 18 + 28);
+        let AocError::NotPolynomial { line, span, .. } = &total.skipped[0] else {
+            panic!("expected a non-polynomial line, got {:?}", total.skipped[0]);
+        };
+        assert_eq!(*line, 2);
+        assert_eq!(&input[span.offset()..span.offset() + span.len()], "1 2 4 8");
+        assert!(matches!(
+            total.skipped[1],
+            AocError::InvalidValue { line: 4, .. }
+        ));
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_overflow() {
+        let squares = sequence(&[0, 1, 4, 9]);
+
+        assert!(squares.forward(1 << 62).is_ok());
+        assert!(matches!(
+            squares.forward(u64::MAX),
+            Err(AocError::Overflow { .. })
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use day_10::part1::process;
+use day_10::pipes::Sketch;
 use miette::Context;
 
 #[cfg(feature = "dhat-heap")]
@@ -14,6 +15,17 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input.txt");
+
+    if std::env::args().any(|arg| arg == "--stray") {
+        let sketch = Sketch::parse(file)?;
+        let stray = sketch.stray(&sketch.loop_path()?);
+        for pos in &stray {
+            println!("stray pipe at row {}, column {}", pos.row + 1, pos.col + 1);
+        }
+        println!("{} stray pipe segments", stray.len());
+        return Ok(());
+    }
+
     let result = process(file).context("process part 1")?;
 
     println!("{}", result);
@@ -1,4 +1,6 @@
 use day_10::part2::process;
+use day_10::pipes::Sketch;
+use day_10::render::render;
 use miette::Context;
 
 #[cfg(feature = "dhat-heap")]
@@ -14,6 +16,13 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input.txt");
+
+    if std::env::args().any(|arg| arg == "--render") {
+        let sketch = Sketch::parse(file)?;
+        let plain = std::env::args().any(|arg| arg == "--plain");
+        print!("{}", render(&sketch, &sketch.loop_path()?, !plain));
+        return Ok(());
+    }
     let result = process(file).context("process part 1")?;
 
     println!("{}", result);
@@ -1,4 +1,4 @@
-use miette::Diagnostic;
+use miette::{Diagnostic, NamedSource, SourceSpan};
 use thiserror::Error;
 
 #[derive(Error, Diagnostic, Debug)]
@@ -6,4 +6,62 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+
+    #[error("unknown tile {tile:?}")]
+    #[diagnostic(code(aoc::unknown_tile), help("tiles are one of | - L J 7 F . S"))]
+    UnknownTile {
+        tile: char,
+        #[source_code]
+        src: NamedSource,
+        #[label("not a tile")]
+        at: SourceSpan,
+    },
+
+    #[error("the sketch has no start tile")]
+    #[diagnostic(code(aoc::missing_start))]
+    MissingStart,
+
+    #[error("the sketch has more than one start tile")]
+    #[diagnostic(code(aoc::extra_start))]
+    ExtraStart {
+        #[source_code]
+        src: NamedSource,
+        #[label("first start")]
+        first: SourceSpan,
+        #[label("another start")]
+        second: SourceSpan,
+    },
+
+    #[error("the start tile connects to {connections} pipes")]
+    #[diagnostic(
+        code(aoc::ambiguous_start),
+        help("the start must connect to exactly two neighbouring pipes for its shape to be known")
+    )]
+    AmbiguousStart {
+        connections: usize,
+        #[source_code]
+        src: NamedSource,
+        #[label("cannot tell which pipe is under here")]
+        at: SourceSpan,
+    },
+
+    #[error("the loop leads off the map")]
+    #[diagnostic(code(aoc::leaves_map))]
+    LeavesMap {
+        #[source_code]
+        src: NamedSource,
+        #[label("this pipe leads off the edge")]
+        at: SourceSpan,
+    },
+
+    #[error("the loop is broken")]
+    #[diagnostic(code(aoc::broken_loop))]
+    BrokenLoop {
+        #[source_code]
+        src: NamedSource,
+        #[label("this pipe leads to")]
+        from: SourceSpan,
+        #[label("a tile that does not connect back")]
+        at: SourceSpan,
+    },
 }
@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+
+use crate::pipes::{Point, Sketch};
+
+/// The tiles enclosed by the loop through `path`, found by flood filling the
+/// sketch scaled up three times.
+///
+/// Every tile becomes a 3×3 block with the pipe drawn through its middle, so
+/// the gaps that let the outside squeeze between two neighbouring pipes are
+/// tiles of their own. Filling from the top left corner, which no pipe on the
+/// loop can cover, reaches everything outside the loop; the middle of every
+/// block it does not reach belongs to an enclosed tile or to the loop.
+pub fn inside(sketch: &Sketch, path: &[Point]) -> HashSet<Point> {
+    let (rows, cols) = sketch.size();
+    let (height, width) = (rows * 3, cols * 3);
+    let mut blocked = vec![false; height * width];
+
+    for pos in path {
+        let (row, col) = (pos.row as usize * 3 + 1, pos.col as usize * 3 + 1);
+        blocked[row * width + col] = true;
+
+        let pipe = sketch.tiles[pos].pipe().unwrap();
+        for end in [pipe.input, pipe.output] {
+            let row = (row as i32 + end.row - pos.row) as usize;
+            let col = (col as i32 + end.col - pos.col) as usize;
+            blocked[row * width + col] = true;
+        }
+    }
+
+    let mut outside = vec![false; height * width];
+    let mut stack = vec![0];
+    outside[0] = true;
+    while let Some(cell) = stack.pop() {
+        let (row, col) = (cell / width, cell % width);
+        let neighbours = [
+            (row > 0).then(|| cell - width),
+            (row + 1 < height).then(|| cell + width),
+            (col > 0).then(|| cell - 1),
+            (col + 1 < width).then(|| cell + 1),
+        ];
+        for next in neighbours.into_iter().flatten() {
+            if !blocked[next] && !outside[next] {
+                outside[next] = true;
+                stack.push(next);
+            }
+        }
+    }
+
+    (0..rows)
+        .flat_map(|row| (0..cols).map(move |col| (row, col)))
+        .filter(|&(row, col)| {
+            let middle = (row * 3 + 1) * width + col * 3 + 1;
+            !outside[middle] && !blocked[middle]
+        })
+        .map(|(row, col)| Point {
+            row: row as i32,
+            col: col as i32,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipes::enclosed;
+
+    #[test_log::test]
+    fn test_matches_shoelace() {
+        for input in [
+            include_str!("../test-input.txt"),
+            include_str!("../test-input2.txt"),
+            include_str!("../test-input3.txt"),
+            include_str!("../test-input4.txt"),
+            include_str!("../test-input5.txt"),
+        ] {
+            let sketch = Sketch::parse(input).unwrap();
+            let path = sketch.loop_path().unwrap();
+
+            assert_eq!(inside(&sketch, &path).len() as u64, enclosed(&path));
+        }
+    }
+
+    #[test_log::test]
+    fn test_squeeze() {
+        // The middle of this loop only opens to the outside through the gap
+        // between the `||` in the bottom half, which no tile fits through.
+        let input = "..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........";
+        let sketch = Sketch::parse(input).unwrap();
+        let path = sketch.loop_path().unwrap();
+        let inside = inside(&sketch, &path);
+
+        assert_eq!(inside.len(), 4);
+        assert_eq!(enclosed(&path), 4);
+        assert!(inside.contains(&Point { row: 6, col: 2 }));
+        assert!(!inside.contains(&Point { row: 3, col: 4 }));
+    }
+}
@@ -1,4 +1,7 @@
 pub mod error;
 
+pub mod flood;
 pub mod part1;
 pub mod part2;
+pub mod pipes;
+pub mod render;
@@ -0,0 +1,352 @@
+use std::collections::{BTreeMap, HashSet};
+
+use miette::{NamedSource, SourceSpan};
+
+use crate::error::AocError;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Hash, Ord, PartialOrd)]
+pub struct Point {
+    pub row: i32,
+    pub col: i32,
+}
+
+impl Point {
+    fn offset(self, (row, col): (i32, i32)) -> Self {
+        Self {
+            row: self.row + row,
+            col: self.col + col,
+        }
+    }
+}
+
+const NORTH: (i32, i32) = (-1, 0);
+const EAST: (i32, i32) = (0, 1);
+const SOUTH: (i32, i32) = (1, 0);
+const WEST: (i32, i32) = (0, -1);
+
+/// Every pipe, with the directions of the tiles it connects.
+const PIPES: [(char, [(i32, i32); 2]); 6] = [
+    ('|', [NORTH, SOUTH]),
+    ('-', [WEST, EAST]),
+    ('L', [NORTH, EAST]),
+    ('J', [NORTH, WEST]),
+    ('7', [SOUTH, WEST]),
+    ('F', [SOUTH, EAST]),
+];
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Pipe {
+    pub input: Point,
+    pub output: Point,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Tile {
+    Vertical(Pipe),
+    Horizontal(Pipe),
+    NorthEast(Pipe),
+    NorthWest(Pipe),
+    SouthWest(Pipe),
+    SouthEast(Pipe),
+    Ground,
+    Start,
+}
+
+impl Tile {
+    fn new(c: char, pos: Point) -> Option<Self> {
+        match c {
+            '.' => return Some(Tile::Ground),
+            'S' => return Some(Tile::Start),
+            _ => {}
+        }
+
+        let (_, [input, output]) = PIPES.iter().find(|(label, _)| *label == c)?;
+        let pipe = Pipe {
+            input: pos.offset(*input),
+            output: pos.offset(*output),
+        };
+        Some(match c {
+            '|' => Tile::Vertical(pipe),
+            '-' => Tile::Horizontal(pipe),
+            'L' => Tile::NorthEast(pipe),
+            'J' => Tile::NorthWest(pipe),
+            '7' => Tile::SouthWest(pipe),
+            _ => Tile::SouthEast(pipe),
+        })
+    }
+
+    pub fn pipe(&self) -> Option<&Pipe> {
+        match self {
+            Tile::Vertical(pipe)
+            | Tile::Horizontal(pipe)
+            | Tile::NorthEast(pipe)
+            | Tile::NorthWest(pipe)
+            | Tile::SouthWest(pipe)
+            | Tile::SouthEast(pipe) => Some(pipe),
+            Tile::Ground | Tile::Start => None,
+        }
+    }
+}
+
+/// A sketch of the pipes, with the tile under `S` worked out from the pipes
+/// connecting to it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Sketch {
+    pub tiles: BTreeMap<Point, Tile>,
+    pub start: Point,
+    input: String,
+    line_starts: Vec<usize>,
+}
+
+impl Sketch {
+    pub fn parse(input: &str) -> Result<Self, AocError> {
+        let mut tiles = BTreeMap::new();
+        let mut line_starts = Vec::new();
+        let mut starts = Vec::new();
+        let mut offset = 0;
+
+        for (row, line) in input.split_inclusive('\n').enumerate() {
+            line_starts.push(offset);
+            for (col, c) in line.trim_end().char_indices() {
+                let pos = Point {
+                    row: row as i32,
+                    col: col as i32,
+                };
+                let tile = Tile::new(c, pos).ok_or_else(|| AocError::UnknownTile {
+                    tile: c,
+                    src: NamedSource::new("input", input.to_string()),
+                    at: SourceSpan::from((offset + col, c.len_utf8())),
+                })?;
+                if tile == Tile::Start {
+                    starts.push(pos);
+                }
+                tiles.insert(pos, tile);
+            }
+            offset += line.len();
+        }
+
+        let mut sketch = Self {
+            tiles,
+            start: Point::default(),
+            input: input.to_string(),
+            line_starts,
+        };
+        match starts[..] {
+            [] => return Err(AocError::MissingStart),
+            [start] => sketch.start = start,
+            [first, second, ..] => {
+                return Err(AocError::ExtraStart {
+                    src: sketch.src(),
+                    first: sketch.span(first),
+                    second: sketch.span(second),
+                })
+            }
+        }
+
+        let start = sketch.start;
+        let connected: Vec<(i32, i32)> = [NORTH, EAST, SOUTH, WEST]
+            .into_iter()
+            .filter(|&direction| {
+                sketch
+                    .tiles
+                    .get(&start.offset(direction))
+                    .and_then(Tile::pipe)
+                    .is_some_and(|pipe| pipe.input == start || pipe.output == start)
+            })
+            .collect();
+        let shape = PIPES.iter().find(|(_, directions)| {
+            connected.len() == 2 && directions.iter().all(|d| connected.contains(d))
+        });
+        let Some((label, _)) = shape else {
+            return Err(AocError::AmbiguousStart {
+                connections: connected.len(),
+                src: sketch.src(),
+                at: sketch.span(start),
+            });
+        };
+        sketch
+            .tiles
+            .insert(start, Tile::new(*label, start).unwrap());
+
+        Ok(sketch)
+    }
+
+    /// The number of rows and columns in the sketch.
+    pub fn size(&self) -> (usize, usize) {
+        let cols = self.tiles.keys().map(|pos| pos.col + 1).max().unwrap_or(0);
+        (self.line_starts.len(), cols as usize)
+    }
+
+    fn src(&self) -> NamedSource {
+        NamedSource::new("input", self.input.clone())
+    }
+
+    fn span(&self, pos: Point) -> SourceSpan {
+        SourceSpan::from((self.line_starts[pos.row as usize] + pos.col as usize, 1))
+    }
+
+    /// The tiles of the main loop in the order they are walked, starting and
+    /// ending next to `S`, with `S` itself last.
+    pub fn loop_path(&self) -> Result<Vec<Point>, AocError> {
+        let start_pipe = self.tiles[&self.start].pipe().unwrap();
+        let mut current = start_pipe.input;
+        let mut prev = self.start;
+        let mut path = vec![current];
+
+        while current != self.start {
+            let pipe = match self.tiles.get(&current) {
+                None => {
+                    return Err(AocError::LeavesMap {
+                        src: self.src(),
+                        at: self.span(prev),
+                    })
+                }
+                Some(tile) => tile
+                    .pipe()
+                    .filter(|pipe| pipe.input == prev || pipe.output == prev),
+            };
+            let Some(pipe) = pipe else {
+                return Err(AocError::BrokenLoop {
+                    src: self.src(),
+                    from: self.span(prev),
+                    at: self.span(current),
+                });
+            };
+
+            let next = if pipe.input == prev {
+                pipe.output
+            } else {
+                pipe.input
+            };
+            prev = current;
+            current = next;
+            path.push(current);
+        }
+
+        Ok(path)
+    }
+
+    /// The pipe tiles that are not part of the loop through `path`.
+    pub fn stray(&self, path: &[Point]) -> Vec<Point> {
+        let on_loop: HashSet<&Point> = path.iter().collect();
+        self.tiles
+            .iter()
+            .filter(|(pos, tile)| tile.pipe().is_some() && !on_loop.contains(pos))
+            .map(|(pos, _)| *pos)
+            .collect()
+    }
+}
+
+/// The number of tiles enclosed by the loop through `path`.
+///
+/// The shoelace formula gives the area of the polygon through the centres of
+/// the loop's tiles, and Pick's theorem, `A = I + B / 2 - 1`, turns that into
+/// the number of lattice points strictly inside it, where every one of the
+/// `B` loop tiles is a boundary point.
+pub fn enclosed(path: &[Point]) -> u64 {
+    let twice_area = path
+        .iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(a, b)| a.col as i64 * b.row as i64 - b.col as i64 * a.row as i64)
+        .sum::<i64>()
+        .unsigned_abs();
+
+    (twice_area + 2).saturating_sub(path.len() as u64) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(input: &str, span: &SourceSpan) -> String {
+        input[span.offset()..span.offset() + span.len()].to_string()
+    }
+
+    #[test_log::test]
+    fn test_infer_start() {
+        let sketch = Sketch::parse(include_str!("../test-input.txt")).unwrap();
+        assert!(matches!(sketch.tiles[&sketch.start], Tile::SouthEast(_)));
+
+        let sketch = Sketch::parse(include_str!("../test-input2.txt")).unwrap();
+        assert!(matches!(sketch.tiles[&sketch.start], Tile::SouthEast(_)));
+    }
+
+    #[test_log::test]
+    fn test_loop_path() {
+        let sketch = Sketch::parse(include_str!("../test-input.txt")).unwrap();
+        let path = sketch.loop_path().unwrap();
+
+        assert_eq!(path.len(), 8);
+        assert_eq!(path.last(), Some(&Point { row: 1, col: 1 }));
+        for (a, b) in path.iter().zip(path.iter().cycle().skip(1)) {
+            assert_eq!((a.row - b.row).abs() + (a.col - b.col).abs(), 1);
+        }
+    }
+
+    #[test_log::test]
+    fn test_stray() {
+        let input = "-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF";
+        let sketch = Sketch::parse(input).unwrap();
+        let path = sketch.loop_path().unwrap();
+
+        assert_eq!(path.len(), 8);
+        assert_eq!(sketch.stray(&path).len(), 25 - 8);
+    }
+
+    #[test_log::test]
+    fn test_ambiguous_start() {
+        let input = ".|.\n-S-\n.|.";
+        let Err(AocError::AmbiguousStart {
+            connections, at, ..
+        }) = Sketch::parse(input)
+        else {
+            panic!("expected an ambiguous start");
+        };
+        assert_eq!(connections, 4);
+        assert_eq!(label(input, &at), "S");
+
+        assert!(matches!(
+            Sketch::parse("...\n.S-\n..."),
+            Err(AocError::AmbiguousStart { connections: 1, .. })
+        ));
+    }
+
+    #[test_log::test]
+    fn test_broken_loop() {
+        let input = ".....\n.S-7.\n.|.|.\n.L-..\n.....";
+        let Err(AocError::BrokenLoop { from, at, .. }) = Sketch::parse(input).unwrap().loop_path()
+        else {
+            panic!("expected a broken loop");
+        };
+        assert_eq!(label(input, &from), "-");
+        assert_eq!(label(input, &at), ".");
+
+        assert!(matches!(
+            Sketch::parse("S-7\n|.|\nL-J\nS.."),
+            Err(AocError::ExtraStart { .. })
+        ));
+        assert!(matches!(
+            Sketch::parse("S-7\n|X|\nL-J"),
+            Err(AocError::UnknownTile { tile: 'X', .. })
+        ));
+        assert!(matches!(Sketch::parse("..."), Err(AocError::MissingStart)));
+    }
+
+    #[test_log::test]
+    fn test_enclosed() {
+        let square = |n: i32| -> Vec<Point> {
+            let mut path = Vec::new();
+            path.extend((0..n).map(|col| Point { row: 0, col }));
+            path.extend((0..n).map(|row| Point { row, col: n }));
+            path.extend((1..=n).rev().map(|col| Point { row: n, col }));
+            path.extend((1..=n).rev().map(|row| Point { row, col: 0 }));
+            path
+        };
+
+        assert_eq!(enclosed(&square(2)), 1);
+        assert_eq!(enclosed(&square(4)), 9);
+
+        let sketch = Sketch::parse(include_str!("../test-input.txt")).unwrap();
+        assert_eq!(enclosed(&sketch.loop_path().unwrap()), 1);
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+
+use crate::flood::inside;
+use crate::pipes::{Point, Sketch, Tile};
+
+const INSIDE: &str = "\x1b[32m";
+const OUTSIDE: &str = "\x1b[34m";
+const RESET: &str = "\x1b[0m";
+
+/// The box-drawing character for a pipe.
+fn glyph(tile: &Tile) -> char {
+    match tile {
+        Tile::Vertical(_) => '│',
+        Tile::Horizontal(_) => '─',
+        Tile::NorthEast(_) => '└',
+        Tile::NorthWest(_) => '┘',
+        Tile::SouthWest(_) => '┐',
+        Tile::SouthEast(_) => '┌',
+        Tile::Ground | Tile::Start => ' ',
+    }
+}
+
+/// The sketch with the loop through `path` drawn in box-drawing characters,
+/// enclosed tiles marked `I` and every other tile marked `O`. With `colour`,
+/// the marks are green and blue as well.
+pub fn render(sketch: &Sketch, path: &[Point], colour: bool) -> String {
+    let on_loop: HashSet<&Point> = path.iter().collect();
+    let inside = inside(sketch, path);
+    let (rows, cols) = sketch.size();
+
+    let mut out = String::new();
+    for row in 0..rows as i32 {
+        for col in 0..cols as i32 {
+            let pos = Point { row, col };
+            if on_loop.contains(&pos) {
+                out.push(glyph(&sketch.tiles[&pos]));
+                continue;
+            }
+
+            let (mark, code) = if inside.contains(&pos) {
+                ('I', INSIDE)
+            } else {
+                ('O', OUTSIDE)
+            };
+            if colour {
+                out.push_str(code);
+                out.push(mark);
+                out.push_str(RESET);
+            } else {
+                out.push(mark);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_render() {
+        let sketch = Sketch::parse(include_str!("../test-input3.txt")).unwrap();
+        let path = sketch.loop_path().unwrap();
+        let rendered = render(&sketch, &path, false);
+
+        assert_eq!(rendered.matches('I').count(), 4);
+        assert_eq!(rendered.lines().nth(1), Some("O┌───────┐O"));
+        assert_eq!(rendered.lines().nth(6), Some("O│II│O│II│O"));
+
+        let coloured = render(&sketch, &path, true);
+        assert!(coloured.contains("\x1b[32mI\x1b[0m"));
+    }
+}
@@ -1,3 +1,4 @@
+use day_11::galaxy::{Expansion, Image};
 use day_11::part2::process;
 use miette::Context;
 
@@ -14,6 +15,28 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input.txt");
+
+    // `--rows=N` and `--cols=N` expand each empty row or column into N.
+    let args: Vec<String> = std::env::args().collect();
+    let factor = |flag: &str| -> miette::Result<Option<u64>> {
+        args.iter()
+            .find_map(|arg| arg.strip_prefix(flag))
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| miette::miette!("{flag}{value} is not a u64 factor"))
+            })
+            .transpose()
+    };
+    let (rows, cols) = (factor("--rows=")?, factor("--cols=")?);
+    if rows.is_some() || cols.is_some() {
+        let expansion = Expansion {
+            rows: rows.unwrap_or(1_000_000),
+            cols: cols.unwrap_or(1_000_000),
+        };
+        println!("{}", Image::parse(file)?.distance_sum(expansion)?);
+        return Ok(());
+    }
     let result = process(file).context("process part 1")?;
 
     println!("{}", result);
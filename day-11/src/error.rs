@@ -1,4 +1,4 @@
-use miette::Diagnostic;
+use miette::{Diagnostic, NamedSource, SourceSpan};
 use thiserror::Error;
 
 #[derive(Error, Diagnostic, Debug)]
@@ -6,4 +6,22 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+
+    #[error("unknown tile {tile:?}")]
+    #[diagnostic(code(aoc::unknown_tile), help("images are made of `#` and `.`"))]
+    UnknownTile {
+        tile: char,
+        #[source_code]
+        src: NamedSource,
+        #[label("this tile")]
+        at: SourceSpan,
+    },
+
+    #[error("expanding empty space by a factor of {factor} overflows a u64 coordinate")]
+    #[diagnostic(code(aoc::overflow))]
+    Overflow { factor: u64 },
+
+    #[error("the sum of the distances does not fit in a u64")]
+    #[diagnostic(code(aoc::sum_overflow))]
+    SumOverflow,
 }
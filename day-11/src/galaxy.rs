@@ -0,0 +1,176 @@
+use miette::{NamedSource, SourceSpan};
+
+use crate::error::AocError;
+
+/// How many rows or columns every empty row or column becomes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Expansion {
+    pub rows: u64,
+    pub cols: u64,
+}
+
+impl Expansion {
+    pub fn uniform(factor: u64) -> Self {
+        Self {
+            rows: factor,
+            cols: factor,
+        }
+    }
+}
+
+/// The galaxies in an image, as their row and column coordinates kept
+/// sorted per axis.
+///
+/// The distance between two galaxies is the sum of their distances along
+/// each axis, so the two axes can be expanded and summed on their own, and
+/// neither needs to know which row goes with which column.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    rows: Vec<u64>,
+    cols: Vec<u64>,
+}
+
+impl Image {
+    pub fn parse(input: &str) -> Result<Self, AocError> {
+        let mut rows = Vec::new();
+        let mut cols = Vec::new();
+        let mut offset = 0;
+
+        for (row, line) in input.split_inclusive('\n').enumerate() {
+            for (col, c) in line.trim_end().char_indices() {
+                match c {
+                    '#' => {
+                        rows.push(row as u64);
+                        cols.push(col as u64);
+                    }
+                    '.' => {}
+                    _ => {
+                        return Err(AocError::UnknownTile {
+                            tile: c,
+                            src: NamedSource::new("input", input.to_string()),
+                            at: SourceSpan::from((offset + col, c.len_utf8())),
+                        })
+                    }
+                }
+            }
+            offset += line.len();
+        }
+
+        // Rows are already in order from reading the image top to bottom.
+        cols.sort_unstable();
+        Ok(Self { rows, cols })
+    }
+
+    pub fn galaxy_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The sum of the shortest paths between every pair of galaxies once
+    /// the empty rows and columns have expanded.
+    pub fn distance_sum(&self, expansion: Expansion) -> Result<u128, AocError> {
+        let rows = expand(&self.rows, expansion.rows)?;
+        let cols = expand(&self.cols, expansion.cols)?;
+        Ok(axis_sum(&rows) + axis_sum(&cols))
+    }
+}
+
+/// Sorted coordinates after every empty line has become `factor` lines.
+///
+/// The number of empty lines before a coordinate is the coordinate minus
+/// the number of distinct occupied lines before it, which a single pass over
+/// the sorted coordinates counts as it goes.
+fn expand(sorted: &[u64], factor: u64) -> Result<Vec<u64>, AocError> {
+    let mut occupied = 0;
+    let mut previous = None;
+
+    sorted
+        .iter()
+        .map(|&coord| {
+            if previous.is_some_and(|previous| previous != coord) {
+                occupied += 1;
+            }
+            previous = Some(coord);
+
+            let empty = coord - occupied;
+            factor
+                .checked_mul(empty)
+                .and_then(|expanded| expanded.checked_add(occupied))
+                .ok_or(AocError::Overflow { factor })
+        })
+        .collect()
+}
+
+/// The sum of `|a - b|` over every pair of the sorted coordinates.
+///
+/// Each coordinate is the larger one in a pair with every coordinate before
+/// it, so it adds itself once per earlier coordinate and subtracts their
+/// running total.
+fn axis_sum(sorted: &[u64]) -> u128 {
+    let mut prefix: u128 = 0;
+    let mut sum: u128 = 0;
+
+    for (i, &coord) in sorted.iter().enumerate() {
+        sum += coord as u128 * i as u128 - prefix;
+        prefix += coord as u128;
+    }
+
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_expand() {
+        assert_eq!(expand(&[0, 0, 2, 5], 2).unwrap(), vec![0, 0, 3, 8]);
+        assert_eq!(expand(&[1, 3], 10).unwrap(), vec![10, 21]);
+        assert_eq!(expand(&[0, 1, 2], 0).unwrap(), vec![0, 1, 2]);
+        assert_eq!(expand(&[1, 3], 0).unwrap(), vec![0, 1]);
+        assert!(matches!(
+            expand(&[2], u64::MAX),
+            Err(AocError::Overflow { .. })
+        ));
+    }
+
+    #[test_log::test]
+    fn test_axis_sum() {
+        assert_eq!(axis_sum(&[]), 0);
+        assert_eq!(axis_sum(&[4]), 0);
+        assert_eq!(axis_sum(&[1, 3, 3, 8]), 2 + 2 + 7 + 5 + 5);
+    }
+
+    #[test_log::test]
+    fn test_distance_sum() -> miette::Result<()> {
+        let image = Image::parse(include_str!("../test-input.txt"))?;
+
+        assert_eq!(image.galaxy_count(), 9);
+        assert_eq!(image.distance_sum(Expansion::uniform(1))?, 292);
+        assert_eq!(image.distance_sum(Expansion::uniform(2))?, 374);
+        assert_eq!(image.distance_sum(Expansion::uniform(10))?, 1030);
+        assert_eq!(image.distance_sum(Expansion::uniform(100))?, 8410);
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_independent_factors() -> miette::Result<()> {
+        let image = Image::parse("#.\n..\n.#")?;
+
+        // Two rows and one column apart, with one empty row between.
+        let expansion = Expansion { rows: 5, cols: 1 };
+        assert_eq!(image.distance_sum(expansion)?, 6 + 1);
+        let expansion = Expansion { rows: 1, cols: 5 };
+        assert_eq!(image.distance_sum(expansion)?, 2 + 1);
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_unknown_tile() {
+        let input = "#.\n.x";
+        let Err(AocError::UnknownTile { tile, at, .. }) = Image::parse(input) else {
+            panic!("expected an unknown tile");
+        };
+        assert_eq!(tile, 'x');
+        assert_eq!(at.offset(), 4);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod error;
 
+pub mod galaxy;
 pub mod part1;
 pub mod part2;